@@ -0,0 +1,177 @@
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+/// A piece of a `#[pattern]`: either literal text or a `{field}`.
+enum Piece {
+    Literal(String),
+    Field(String),
+}
+
+/// Splits a pattern like `move {n} from {from} to {to}` into pieces. `{{`
+/// and `}}` stand for literal braces.
+fn split_pattern(pattern: &str) -> Result<Vec<Piece>, String> {
+    let mut pieces = vec![];
+    let mut literal = String::new();
+    let mut chars = pattern.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let mut name = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => name.push(c),
+                        None => return Err(String::from("unclosed '{' in pattern")),
+                    }
+                }
+
+                if matches!(pieces.last(), Some(Piece::Field(_))) && literal.is_empty() {
+                    return Err(format!(
+                        "field {{{}}} must be separated from the previous field by some text",
+                        name
+                    ));
+                }
+
+                pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                pieces.push(Piece::Field(name));
+            }
+            '}' => {
+                return Err(String::from(
+                    "unmatched '}' in pattern, use '}}' for a literal brace",
+                ))
+            }
+            c => literal.push(c),
+        }
+    }
+
+    pieces.push(Piece::Literal(literal));
+
+    Ok(pieces)
+}
+
+/// Derives `FromLine` for a struct with named fields, parsing a line that
+/// follows the `#[pattern("...")]` attribute. Every `{name}` in the pattern
+/// is parsed into the struct field of that name with `FromStr`.
+///
+/// ```ignore
+/// #[derive(FromLine)]
+/// #[pattern("move {n} from {from} to {to}")]
+/// struct Move {
+///     n: usize,
+///     from: usize,
+///     to: usize,
+/// }
+/// ```
+#[proc_macro_derive(FromLine, attributes(pattern))]
+pub fn derive_from_line(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+fn expand(input: DeriveInput) -> Result<proc_macro2::TokenStream, Error> {
+    let name = &input.ident;
+
+    let fields = match &input.data {
+        Data::Struct(s) => match &s.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(Error::new_spanned(
+                    name,
+                    "FromLine needs a struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(Error::new_spanned(
+                name,
+                "FromLine can only be derived for structs",
+            ))
+        }
+    };
+
+    let attr = input
+        .attrs
+        .iter()
+        .find(|a| a.path().is_ident("pattern"))
+        .ok_or_else(|| Error::new(Span::call_site(), "missing #[pattern(\"...\")] attribute"))?;
+    let pattern: LitStr = attr.parse_args()?;
+
+    let pieces = split_pattern(&pattern.value()).map_err(|e| Error::new(pattern.span(), e))?;
+
+    let literals: Vec<&str> = pieces
+        .iter()
+        .filter_map(|p| match p {
+            Piece::Literal(l) => Some(l.as_str()),
+            Piece::Field(_) => None,
+        })
+        .collect();
+    let names: Vec<&str> = pieces
+        .iter()
+        .filter_map(|p| match p {
+            Piece::Field(f) => Some(f.as_str()),
+            Piece::Literal(_) => None,
+        })
+        .collect();
+
+    for n in &names {
+        if !fields
+            .iter()
+            .any(|f| f.ident.as_ref().is_some_and(|i| i == n))
+        {
+            return Err(Error::new(
+                pattern.span(),
+                format!("{} has no field `{}`", name, n),
+            ));
+        }
+        if names.iter().filter(|m| *m == n).count() > 1 {
+            return Err(Error::new(
+                pattern.span(),
+                format!("field `{}` appears more than once", n),
+            ));
+        }
+    }
+
+    let mut inits = vec![];
+    for f in fields {
+        let ident = f.ident.as_ref().expect("named field");
+        let index = names.iter().position(|n| ident == n).ok_or_else(|| {
+            Error::new_spanned(
+                ident,
+                format!("field `{}` is missing from the pattern", ident),
+            )
+        })?;
+        let field_name = ident.to_string();
+
+        inits.push(quote! {
+            #ident: ::aoc2022::parse::parse_named_field(#field_name, fields[#index].0, fields[#index].1)?
+        });
+    }
+
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    Ok(quote! {
+        impl #impl_generics ::aoc2022::parse::FromLine for #name #ty_generics #where_clause {
+            fn from_line(line: &str) -> ::core::result::Result<Self, ::aoc2022::parse::ParseError> {
+                let fields = ::aoc2022::parse::match_fields(line, &[#(#literals),*])?;
+
+                ::core::result::Result::Ok(#name {
+                    #(#inits),*
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,231 @@
+use crate::{Dir, Matrix, Neighbors, Pos};
+
+/// Which cells count as touching when filling or labelling regions.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Connectivity {
+    /// Cells touch if they share an edge.
+    Four,
+    /// Cells touch if they share an edge or a corner.
+    Eight,
+}
+
+/// Size and shape of one region found by [Matrix::label_components].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ComponentStats {
+    /// The label of the region in the labelled matrix.
+    pub label: usize,
+    /// The number of cells in the region.
+    pub area: usize,
+    /// The number of cell edges between the region and anything outside it,
+    /// including the edge of the matrix.
+    pub perimeter: usize,
+}
+
+impl<T> Matrix<T> {
+    fn neighbors(&self, pos: (usize, usize), connectivity: Connectivity) -> Neighbors<'_, T> {
+        match connectivity {
+            Connectivity::Four => self.neighbors4(pos),
+            Connectivity::Eight => self.neighbors8(pos),
+        }
+    }
+
+    /// Returns every cell reachable from `start` through cells for which
+    /// `pred` returns true, in the order they were reached. Returns nothing
+    /// if `start` itself does not match.
+    pub fn flood_fill<P>(
+        &self,
+        start: (usize, usize),
+        connectivity: Connectivity,
+        mut pred: P,
+    ) -> Vec<(usize, usize)>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        if !pred(&self[start]) {
+            return vec![];
+        }
+
+        let mut seen = Matrix::filled(self.rows, self.columns, false);
+        let mut filled = vec![start];
+        let mut stack = vec![start];
+        seen[start] = true;
+
+        while let Some(pos) = stack.pop() {
+            for (next, value) in self.neighbors(pos, connectivity) {
+                if !seen[next] && pred(value) {
+                    seen[next] = true;
+                    filled.push(next);
+                    stack.push(next);
+                }
+            }
+        }
+
+        filled
+    }
+
+    /// Labels every connected region of cells for which `pred` returns
+    /// true. Regions are numbered from 1 in row-major order of their first
+    /// cell, and cells outside all regions get label 0.
+    pub fn label_components<P>(&self, connectivity: Connectivity, mut pred: P) -> Matrix<usize>
+    where
+        P: FnMut(&T) -> bool,
+    {
+        let matching: Vec<bool> = self.data.iter().map(&mut pred).collect();
+        let mut labels = Matrix::filled(self.rows, self.columns, 0);
+        let mut next_label = 1;
+
+        for r in 0..self.rows {
+            for c in 0..self.columns {
+                if !matching[r * self.columns + c] || labels[(r, c)] != 0 {
+                    continue;
+                }
+
+                let mut stack = vec![(r, c)];
+                labels[(r, c)] = next_label;
+
+                while let Some(pos) = stack.pop() {
+                    for (next, _) in self.neighbors(pos, connectivity) {
+                        if matching[next.0 * self.columns + next.1] && labels[next] == 0 {
+                            labels[next] = next_label;
+                            stack.push(next);
+                        }
+                    }
+                }
+
+                next_label += 1;
+            }
+        }
+
+        labels
+    }
+}
+
+impl Matrix<usize> {
+    /// Returns the area and perimeter of each region in a matrix returned by
+    /// [Matrix::label_components], ordered by label.
+    pub fn component_stats(&self) -> Vec<ComponentStats> {
+        let count = self.data.iter().copied().max().unwrap_or(0);
+        let mut stats: Vec<ComponentStats> = (1..=count)
+            .map(|label| ComponentStats {
+                label,
+                area: 0,
+                perimeter: 0,
+            })
+            .collect();
+
+        for r in 0..self.rows {
+            for c in 0..self.columns {
+                let label = self[(r, c)];
+                if label == 0 {
+                    continue;
+                }
+
+                let s = &mut stats[label - 1];
+                s.area += 1;
+                s.perimeter += Dir::CARDINAL
+                    .iter()
+                    .filter(|&&d| {
+                        let next = self.index_of(Pos::from((r, c)) + d);
+                        next.is_none_or(|p| self[p] != label)
+                    })
+                    .count();
+            }
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regions() -> Matrix<char> {
+        Matrix::from_str_with("##..#\n#...#\n..#..\n.#..#", |c| c).unwrap()
+    }
+
+    #[test]
+    fn fill() {
+        let m = regions();
+
+        let mut filled = m.flood_fill((0, 0), Connectivity::Four, |c| *c == '#');
+        filled.sort();
+
+        assert_eq!(filled, vec![(0, 0), (0, 1), (1, 0)]);
+        assert_eq!(
+            m.flood_fill((0, 2), Connectivity::Four, |c| *c == '#'),
+            vec![]
+        );
+        assert_eq!(
+            m.flood_fill((0, 2), Connectivity::Four, |c| *c == '.')
+                .len(),
+            12
+        );
+        assert_eq!(
+            m.flood_fill((2, 2), Connectivity::Four, |c| *c == '#')
+                .len(),
+            1
+        );
+        assert_eq!(
+            m.flood_fill((3, 1), Connectivity::Eight, |c| *c == '#')
+                .len(),
+            2
+        );
+    }
+
+    #[test]
+    fn components() {
+        let m = regions();
+
+        let four = m.label_components(Connectivity::Four, |c| *c == '#');
+        assert_eq!(
+            four.render(|l| char::from_digit(*l as u32, 10).unwrap()),
+            "11002\n10002\n00300\n04005\n"
+        );
+
+        let eight = m.label_components(Connectivity::Eight, |c| *c == '#');
+        assert_eq!(
+            eight.render(|l| char::from_digit(*l as u32, 10).unwrap()),
+            "11002\n10002\n00300\n03004\n"
+        );
+
+        assert_eq!(
+            four.component_stats(),
+            vec![
+                ComponentStats {
+                    label: 1,
+                    area: 3,
+                    perimeter: 8
+                },
+                ComponentStats {
+                    label: 2,
+                    area: 2,
+                    perimeter: 6
+                },
+                ComponentStats {
+                    label: 3,
+                    area: 1,
+                    perimeter: 4
+                },
+                ComponentStats {
+                    label: 4,
+                    area: 1,
+                    perimeter: 4
+                },
+                ComponentStats {
+                    label: 5,
+                    area: 1,
+                    perimeter: 4
+                },
+            ]
+        );
+        assert_eq!(
+            eight.component_stats()[2],
+            ComponentStats {
+                label: 3,
+                area: 2,
+                perimeter: 8
+            }
+        );
+    }
+}
@@ -0,0 +1,363 @@
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::ops::{Index, IndexMut};
+
+/// A signed position in a [SparseGrid3<T>].
+pub type Pos3 = (i64, i64, i64);
+
+/// The six face-sharing neighbour offsets of a cell in three dimensions.
+const FACES: [Pos3; 6] = [
+    (-1, 0, 0),
+    (1, 0, 0),
+    (0, -1, 0),
+    (0, 1, 0),
+    (0, 0, -1),
+    (0, 0, 1),
+];
+
+fn offset((x, y, z): Pos3, (dx, dy, dz): Pos3) -> Pos3 {
+    (x + dx, y + dy, z + dz)
+}
+
+/// Counts the faces of solid cells that can be reached from outside the box
+/// between `min` and `max` (inclusive) without passing through solid cells.
+fn exterior_faces<F>(min: Pos3, max: Pos3, is_solid: F) -> usize
+where
+    F: Fn(Pos3) -> bool,
+{
+    let min = offset(min, (-1, -1, -1));
+    let max = offset(max, (1, 1, 1));
+    let inside = |(x, y, z): Pos3| {
+        (min.0..=max.0).contains(&x) && (min.1..=max.1).contains(&y) && (min.2..=max.2).contains(&z)
+    };
+
+    let mut seen = HashSet::from([min]);
+    let mut queue = VecDeque::from([min]);
+    let mut faces = 0;
+
+    while let Some(pos) = queue.pop_front() {
+        for d in FACES {
+            let next = offset(pos, d);
+            if !inside(next) {
+                continue;
+            }
+
+            if is_solid(next) {
+                faces += 1;
+            } else if seen.insert(next) {
+                queue.push_back(next);
+            }
+        }
+    }
+
+    faces
+}
+
+/// A dense three-dimensional grid of elements of type `T`, modelled on
+/// [crate::Matrix]. Positions are `(x, y, z)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Grid3<T> {
+    size: (usize, usize, usize),
+    data: Vec<T>,
+}
+
+impl<T: Clone> Grid3<T> {
+    /// Creates a grid of the given size with every element set to `value`.
+    pub fn filled(size: (usize, usize, usize), value: T) -> Self {
+        Grid3 {
+            size,
+            data: vec![value; size.0 * size.1 * size.2],
+        }
+    }
+}
+
+impl<T> Grid3<T> {
+    /// Returns the size along the x, y and z axes.
+    pub fn size(&self) -> (usize, usize, usize) {
+        self.size
+    }
+
+    fn index_of(&self, (x, y, z): (usize, usize, usize)) -> Option<usize> {
+        let (sx, sy, sz) = self.size;
+        (x < sx && y < sy && z < sz).then_some((z * sy + y) * sx + x)
+    }
+
+    fn signed_index_of(&self, (x, y, z): Pos3) -> Option<usize> {
+        let pos = (
+            usize::try_from(x).ok()?,
+            usize::try_from(y).ok()?,
+            usize::try_from(z).ok()?,
+        );
+        self.index_of(pos)
+    }
+
+    /// Returns a reference to the element at `pos`, or `None` if it is
+    /// outside the grid.
+    pub fn get(&self, pos: (usize, usize, usize)) -> Option<&T> {
+        self.index_of(pos).map(|i| &self.data[i])
+    }
+
+    /// Returns a mutable reference to the element at `pos`, or `None` if it
+    /// is outside the grid.
+    pub fn get_mut(&mut self, pos: (usize, usize, usize)) -> Option<&mut T> {
+        self.index_of(pos).map(|i| &mut self.data[i])
+    }
+
+    /// Returns the up to six cells sharing a face with `pos`, together with
+    /// their positions.
+    pub fn neighbors6(
+        &self,
+        pos: (usize, usize, usize),
+    ) -> impl Iterator<Item = ((usize, usize, usize), &T)> {
+        let signed = (pos.0 as i64, pos.1 as i64, pos.2 as i64);
+
+        FACES.into_iter().filter_map(move |d| {
+            let (x, y, z) = offset(signed, d);
+            let i = self.signed_index_of((x, y, z))?;
+            Some(((x as usize, y as usize, z as usize), &self.data[i]))
+        })
+    }
+
+    fn is_solid<P>(&self, pos: Pos3, pred: &P) -> bool
+    where
+        P: Fn(&T) -> bool,
+    {
+        self.signed_index_of(pos)
+            .is_some_and(|i| pred(&self.data[i]))
+    }
+
+    /// Counts the faces of cells for which `pred` returns true that do not
+    /// touch another such cell, including faces inside enclosed pockets.
+    pub fn surface_area<P>(&self, pred: P) -> usize
+    where
+        P: Fn(&T) -> bool,
+    {
+        let (sx, sy, _) = self.size;
+
+        self.data
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| pred(t))
+            .map(|(i, _)| {
+                let pos = (
+                    (i % sx) as i64,
+                    (i / sx % sy) as i64,
+                    (i / (sx * sy)) as i64,
+                );
+                FACES
+                    .iter()
+                    .filter(|&&d| !self.is_solid(offset(pos, d), &pred))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// Like [Grid3::surface_area], but only counts faces that can be reached
+    /// from outside the grid.
+    pub fn exterior_surface_area<P>(&self, pred: P) -> usize
+    where
+        P: Fn(&T) -> bool,
+    {
+        let (sx, sy, sz) = self.size;
+        if self.data.is_empty() {
+            return 0;
+        }
+
+        exterior_faces(
+            (0, 0, 0),
+            (sx as i64 - 1, sy as i64 - 1, sz as i64 - 1),
+            |pos| self.is_solid(pos, &pred),
+        )
+    }
+}
+
+impl<T> Index<(usize, usize, usize)> for Grid3<T> {
+    type Output = T;
+
+    fn index(&self, pos: (usize, usize, usize)) -> &T {
+        self.get(pos).expect("valid position")
+    }
+}
+
+impl<T> IndexMut<(usize, usize, usize)> for Grid3<T> {
+    fn index_mut(&mut self, pos: (usize, usize, usize)) -> &mut T {
+        self.get_mut(pos).expect("valid position")
+    }
+}
+
+/// An unbounded three-dimensional grid that only stores occupied cells,
+/// keyed by signed `(x, y, z)` positions.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid3<T> {
+    cells: BTreeMap<Pos3, T>,
+}
+
+impl<T> Default for SparseGrid3<T> {
+    fn default() -> Self {
+        SparseGrid3 {
+            cells: BTreeMap::new(),
+        }
+    }
+}
+
+impl<T> SparseGrid3<T> {
+    /// Creates an empty grid.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of occupied cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns true if no cells are occupied.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns a reference to the value at `pos`, if occupied.
+    pub fn get(&self, pos: Pos3) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    /// Returns true if `pos` is occupied.
+    pub fn contains(&self, pos: Pos3) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    /// Stores `value` at `pos`, returning the previous value if the cell was
+    /// already occupied.
+    pub fn insert(&mut self, pos: Pos3, value: T) -> Option<T> {
+        self.cells.insert(pos, value)
+    }
+
+    /// Clears the cell at `pos`, returning its value if it was occupied.
+    pub fn remove(&mut self, pos: Pos3) -> Option<T> {
+        self.cells.remove(&pos)
+    }
+
+    /// Returns an iterator over the occupied cells.
+    pub fn iter(&self) -> impl Iterator<Item = (Pos3, &T)> {
+        self.cells.iter().map(|(&pos, v)| (pos, v))
+    }
+
+    /// Returns the smallest and largest corner of the box containing every
+    /// occupied cell, or `None` if the grid is empty.
+    pub fn bounds(&self) -> Option<(Pos3, Pos3)> {
+        let mut positions = self.cells.keys();
+        let first = *positions.next()?;
+
+        Some(positions.fold((first, first), |(min, max), &(x, y, z)| {
+            (
+                (min.0.min(x), min.1.min(y), min.2.min(z)),
+                (max.0.max(x), max.1.max(y), max.2.max(z)),
+            )
+        }))
+    }
+
+    /// Returns the occupied cells sharing a face with `pos`, together with
+    /// their positions.
+    pub fn neighbors6(&self, pos: Pos3) -> impl Iterator<Item = (Pos3, &T)> {
+        FACES.into_iter().filter_map(move |d| {
+            let next = offset(pos, d);
+            self.get(next).map(|v| (next, v))
+        })
+    }
+
+    /// Counts the faces of occupied cells that do not touch another occupied
+    /// cell, including faces inside enclosed pockets.
+    pub fn surface_area(&self) -> usize {
+        self.cells
+            .keys()
+            .map(|&pos| 6 - self.neighbors6(pos).count())
+            .sum()
+    }
+
+    /// Like [SparseGrid3::surface_area], but only counts faces that can be
+    /// reached from outside the bounding box.
+    pub fn exterior_surface_area(&self) -> usize {
+        match self.bounds() {
+            Some((min, max)) => exterior_faces(min, max, |pos| self.contains(pos)),
+            None => 0,
+        }
+    }
+}
+
+impl<T: Clone> SparseGrid3<T> {
+    /// Copies the occupied region into a dense grid, using `fill` for empty
+    /// cells. The smallest corner of [SparseGrid3::bounds] becomes
+    /// `(0, 0, 0)`.
+    pub fn to_grid3(&self, fill: T) -> Grid3<T> {
+        let Some((min, max)) = self.bounds() else {
+            return Grid3 {
+                size: (0, 0, 0),
+                data: vec![],
+            };
+        };
+
+        let size = (
+            (max.0 - min.0) as usize + 1,
+            (max.1 - min.1) as usize + 1,
+            (max.2 - min.2) as usize + 1,
+        );
+        let mut grid = Grid3::filled(size, fill);
+
+        for (&(x, y, z), v) in &self.cells {
+            let pos = (
+                (x - min.0) as usize,
+                (y - min.1) as usize,
+                (z - min.2) as usize,
+            );
+            grid[pos] = v.clone();
+        }
+
+        grid
+    }
+}
+
+impl<T> FromIterator<(Pos3, T)> for SparseGrid3<T> {
+    fn from_iter<I: IntoIterator<Item = (Pos3, T)>>(iter: I) -> Self {
+        SparseGrid3 {
+            cells: iter.into_iter().collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DROPLET: &str =
+        "2,2,2\n1,2,2\n3,2,2\n2,1,2\n2,3,2\n2,2,1\n2,2,3\n2,2,4\n2,2,6\n1,2,5\n3,2,5\n2,1,5\n2,3,5";
+
+    fn droplet() -> SparseGrid3<bool> {
+        DROPLET
+            .lines()
+            .map(|l| {
+                let v: Vec<i64> = l.split(',').map(|n| n.parse().unwrap()).collect();
+                ((v[0], v[1], v[2]), true)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sparse() {
+        let g = droplet();
+
+        assert_eq!(g.bounds(), Some(((1, 1, 1), (3, 3, 6))));
+        assert_eq!(g.neighbors6((2, 2, 2)).count(), 6);
+        assert_eq!(g.surface_area(), 64);
+        assert_eq!(g.exterior_surface_area(), 58);
+    }
+
+    #[test]
+    fn dense() {
+        let solid = droplet().to_grid3(false);
+
+        assert_eq!(solid.size(), (3, 3, 6));
+        assert_eq!(solid.surface_area(|s| *s), 64);
+        assert_eq!(solid.exterior_surface_area(|s| *s), 58);
+        assert_eq!(solid.neighbors6((0, 0, 0)).count(), 3);
+        assert_eq!(solid.neighbors6((1, 1, 1)).filter(|(_, s)| **s).count(), 6);
+    }
+}
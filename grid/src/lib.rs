@@ -0,0 +1,307 @@
+use std::ops::{Index, IndexMut};
+
+mod fill;
+mod grid3;
+mod neighbors;
+mod parse;
+mod path;
+mod pos;
+mod ray;
+mod render;
+mod sparse;
+mod view;
+
+pub use fill::{ComponentStats, Connectivity};
+pub use grid3::{Grid3, Pos3, SparseGrid3};
+pub use neighbors::Neighbors;
+pub use parse::ParseMatrixError;
+pub use path::Path;
+pub use pos::{Dir, Pos};
+pub use ray::{Ray, TakeUntilBlocking, TakeUntilBlockingIter};
+pub use render::{Color, Image};
+pub use sparse::{Bounds, SparseGrid};
+pub use view::{LineIter, View};
+
+/// A dense two-dimensional grid of elements of type `T`, stored in row-major
+/// order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Matrix<T> {
+    rows: usize,
+    columns: usize,
+    data: Vec<T>,
+}
+
+impl<T> Matrix<T> {
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// Returns a reference to the element at row `r` and column `c`, or
+    /// `None` if the position is outside the matrix.
+    pub fn get(&self, r: usize, c: usize) -> Option<&T> {
+        if r < self.rows && c < self.columns {
+            self.data.get(r * self.columns + c)
+        } else {
+            None
+        }
+    }
+
+    /// Returns a mutable reference to the element at row `r` and column `c`,
+    /// or `None` if the position is outside the matrix.
+    pub fn get_mut(&mut self, r: usize, c: usize) -> Option<&mut T> {
+        if r < self.rows && c < self.columns {
+            self.data.get_mut(r * self.columns + c)
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the elements of row `r`, from left to right.
+    /// Panics if the row is out of range.
+    pub fn row(&self, r: usize) -> RowIter<'_, T> {
+        assert!(r < self.rows, "row {} out of range", r);
+
+        let start = r * self.columns;
+        RowIter {
+            inner: self.data[start..start + self.columns].iter(),
+        }
+    }
+
+    /// Returns an iterator over the elements of column `c`, from top to
+    /// bottom. Panics if the column is out of range.
+    pub fn column(&self, c: usize) -> ColumnIter<'_, T> {
+        assert!(c < self.columns, "column {} out of range", c);
+
+        ColumnIter {
+            column: c,
+            columns: self.columns,
+            front: 0,
+            back: self.rows,
+            data: &self.data,
+        }
+    }
+}
+
+impl<T: Clone> Matrix<T> {
+    /// Creates a matrix with every element set to `value`.
+    pub fn filled(rows: usize, columns: usize, value: T) -> Self {
+        Matrix {
+            rows,
+            columns,
+            data: vec![value; rows * columns],
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for Matrix<T> {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &T {
+        self.get(r, c).expect("valid position")
+    }
+}
+
+impl<T> IndexMut<(usize, usize)> for Matrix<T> {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut T {
+        self.get_mut(r, c).expect("valid position")
+    }
+}
+
+/// An iterator over a single row of a [Matrix<T>].
+pub struct RowIter<'a, T> {
+    inner: std::slice::Iter<'a, T>,
+}
+
+/// An iterator over a single column of a [Matrix<T>].
+pub struct ColumnIter<'a, T> {
+    column: usize,
+    columns: usize,
+    front: usize,
+    back: usize,
+    data: &'a [T],
+}
+
+impl<'a, T> Iterator for RowIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<T> DoubleEndedIterator for RowIter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner.next_back()
+    }
+}
+
+impl<T> ExactSizeIterator for RowIter<'_, T> {}
+
+impl<'a, T> Iterator for ColumnIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        let item = &self.data[self.front * self.columns + self.column];
+        self.front += 1;
+
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for ColumnIter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+
+        Some(&self.data[self.back * self.columns + self.column])
+    }
+}
+
+impl<T> ExactSizeIterator for ColumnIter<'_, T> {}
+
+/// Builds a [Matrix<T>] from its dimensions and row-major data.
+pub struct MatrixBuilder<T> {
+    rows: usize,
+    columns: usize,
+    data: Vec<T>,
+}
+
+impl<T> Default for MatrixBuilder<T> {
+    fn default() -> Self {
+        MatrixBuilder {
+            rows: 0,
+            columns: 0,
+            data: vec![],
+        }
+    }
+}
+
+impl<T: Clone> MatrixBuilder<T> {
+    /// Creates an empty builder.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the number of rows.
+    pub fn set_rows(mut self, r: usize) -> Self {
+        self.rows = r;
+
+        self
+    }
+
+    /// Sets the number of columns.
+    pub fn set_columns(mut self, c: usize) -> Self {
+        self.columns = c;
+
+        self
+    }
+
+    /// Appends elements to the data, in row-major order.
+    pub fn add_data(mut self, data: &[T]) -> Self {
+        self.data.extend(data.iter().cloned());
+
+        self
+    }
+
+    /// Creates the matrix. Panics if the amount of data does not match the
+    /// dimensions.
+    pub fn build(self) -> Matrix<T> {
+        if self.rows * self.columns != self.data.len() {
+            panic!("Cannot create Matrix");
+        }
+
+        Matrix {
+            rows: self.rows,
+            columns: self.columns,
+            data: self.data,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn three_by_three() -> Matrix<i32> {
+        MatrixBuilder::new()
+            .set_columns(3)
+            .set_rows(3)
+            .add_data(&[1, 2, 3])
+            .add_data(&[4, 5, 6])
+            .add_data(&[7, 8, 9])
+            .build()
+    }
+
+    #[test]
+    fn matrix() {
+        let m = three_by_three();
+
+        let mut it = m.row(1);
+
+        assert_eq!(it.next(), Some(&4));
+        assert_eq!(it.next(), Some(&5));
+        assert_eq!(it.next(), Some(&6));
+        assert_eq!(it.next(), None);
+
+        let mut it = m.column(1);
+
+        assert_eq!(it.next(), Some(&2));
+        assert_eq!(it.next(), Some(&5));
+        assert_eq!(it.next(), Some(&8));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn reverse() {
+        let m = three_by_three();
+
+        assert_eq!(m.row(2).rev().collect::<Vec<_>>(), vec![&9, &8, &7]);
+        assert_eq!(m.column(0).rev().collect::<Vec<_>>(), vec![&7, &4, &1]);
+
+        let mut it = m.column(2);
+
+        assert_eq!(it.next(), Some(&3));
+        assert_eq!(it.next_back(), Some(&9));
+        assert_eq!(it.len(), 1);
+        assert_eq!(it.next_back(), Some(&6));
+        assert_eq!(it.next(), None);
+    }
+
+    #[test]
+    fn index() {
+        let mut m = three_by_three();
+
+        assert_eq!(m[(1, 2)], 6);
+        assert_eq!(m.get(2, 0), Some(&7));
+        assert_eq!(m.get(3, 0), None);
+        assert_eq!(m.get(0, 3), None);
+
+        m[(0, 0)] = 10;
+        *m.get_mut(2, 2).unwrap() += 1;
+
+        assert_eq!(m.row(0).next(), Some(&10));
+        assert_eq!(m[(2, 2)], 10);
+    }
+}
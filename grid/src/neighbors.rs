@@ -0,0 +1,140 @@
+use crate::{Dir, Matrix, Pos};
+
+impl<T> Matrix<T> {
+    /// Returns the up to four cells sharing an edge with `pos`, together
+    /// with their positions. Cells outside the matrix are skipped.
+    pub fn neighbors4(&self, pos: (usize, usize)) -> Neighbors<'_, T> {
+        Neighbors::new(self, pos, &Dir::CARDINAL, false)
+    }
+
+    /// Returns the up to eight cells sharing an edge or a corner with `pos`,
+    /// together with their positions. Cells outside the matrix are skipped.
+    pub fn neighbors8(&self, pos: (usize, usize)) -> Neighbors<'_, T> {
+        Neighbors::new(self, pos, &Dir::ALL, false)
+    }
+
+    /// Returns the four cells sharing an edge with `pos`, together with
+    /// their positions, treating the matrix as wrapping around at the edges.
+    /// On a matrix less than three cells wide or tall some of these are the
+    /// same cell, or `pos` itself; each cell is yielded at most once and
+    /// `pos` never is.
+    pub fn neighbors_wrapping(&self, pos: (usize, usize)) -> Neighbors<'_, T> {
+        Neighbors::new(self, pos, &Dir::CARDINAL, true)
+    }
+}
+
+/// An iterator over the neighbours of a cell in a [Matrix<T>], yielding
+/// `((row, column), &T)`.
+pub struct Neighbors<'a, T> {
+    matrix: &'a Matrix<T>,
+    pos: Pos,
+    dirs: &'static [Dir],
+    next_dir: usize,
+    wrap: bool,
+}
+
+impl<'a, T> Neighbors<'a, T> {
+    fn new(matrix: &'a Matrix<T>, pos: (usize, usize), dirs: &'static [Dir], wrap: bool) -> Self {
+        Neighbors {
+            matrix,
+            pos: Pos::from(pos),
+            // An empty matrix has no neighbours to wrap around to.
+            dirs: if matrix.rows() == 0 || matrix.columns() == 0 {
+                &[]
+            } else {
+                dirs
+            },
+            next_dir: 0,
+            wrap,
+        }
+    }
+
+    /// Returns the position one step in `dir`, wrapped around if needed.
+    fn step(&self, dir: Dir) -> Pos {
+        let mut next = self.pos + dir;
+
+        if self.wrap {
+            next.row = next.row.rem_euclid(self.matrix.rows() as isize);
+            next.col = next.col.rem_euclid(self.matrix.columns() as isize);
+        }
+
+        next
+    }
+}
+
+impl<'a, T> Iterator for Neighbors<'a, T> {
+    type Item = ((usize, usize), &'a T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(&dir) = self.dirs.get(self.next_dir) {
+            let earlier = &self.dirs[..self.next_dir];
+            self.next_dir += 1;
+
+            let next = self.step(dir);
+            if self.wrap && (next == self.pos || earlier.iter().any(|&d| self.step(d) == next)) {
+                continue;
+            }
+
+            if let Some((r, c)) = self.matrix.index_of(next) {
+                return Some(((r, c), &self.matrix[(r, c)]));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Matrix;
+
+    fn positions<'a>(it: impl Iterator<Item = ((usize, usize), &'a u8)>) -> Vec<(usize, usize)> {
+        it.map(|(p, _)| p).collect()
+    }
+
+    #[test]
+    fn edges() {
+        let m = Matrix::from_digits("123\n456\n789").unwrap();
+
+        assert_eq!(positions(m.neighbors4((0, 0))), vec![(0, 1), (1, 0)]);
+        assert_eq!(m.neighbors4((1, 1)).count(), 4);
+        assert_eq!(
+            m.neighbors8((0, 1)).map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec![3, 6, 5, 4, 1]
+        );
+        assert_eq!(m.neighbors8((1, 1)).count(), 8);
+    }
+
+    #[test]
+    fn wrapping() {
+        let m = Matrix::from_digits("123\n456\n789").unwrap();
+
+        assert_eq!(
+            positions(m.neighbors_wrapping((0, 0))),
+            vec![(2, 0), (0, 1), (1, 0), (0, 2)]
+        );
+    }
+
+    #[test]
+    fn wrapping_small() {
+        let m = Matrix::from_digits("1").unwrap();
+
+        assert_eq!(m.neighbors_wrapping((0, 0)).count(), 0);
+
+        let m = Matrix::from_digits("12").unwrap();
+
+        assert_eq!(positions(m.neighbors_wrapping((0, 0))), vec![(0, 1)]);
+
+        let m = Matrix::from_digits("12\n34").unwrap();
+
+        assert_eq!(
+            positions(m.neighbors_wrapping((1, 1))),
+            vec![(0, 1), (1, 0)]
+        );
+
+        let m: Matrix<u8> = Matrix::filled(0, 3, 0);
+
+        assert_eq!(m.neighbors_wrapping((0, 0)).count(), 0);
+        assert_eq!(m.neighbors4((0, 0)).count(), 0);
+    }
+}
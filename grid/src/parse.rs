@@ -0,0 +1,133 @@
+use crate::Matrix;
+
+/// Error returned when a [Matrix<T>] cannot be parsed from text.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseMatrixError {
+    /// Row `row` has `len` cells while the rows before it have `expected`.
+    Ragged {
+        row: usize,
+        len: usize,
+        expected: usize,
+    },
+    /// The cell at `row`, `column` is not a decimal digit.
+    NotADigit { row: usize, column: usize, c: char },
+}
+
+impl std::fmt::Display for ParseMatrixError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseMatrixError::Ragged { row, len, expected } => {
+                write!(f, "row {} has length {}, expected {}", row, len, expected)
+            }
+            ParseMatrixError::NotADigit { row, column, c } => {
+                write!(
+                    f,
+                    "{:?} at row {}, column {} is not a digit",
+                    c, row, column
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseMatrixError {}
+
+impl<T> Matrix<T> {
+    /// Parses a matrix from text with one row per line, converting each
+    /// character with `f`. All lines must have the same number of characters.
+    pub fn from_str_with<F>(text: &str, mut f: F) -> Result<Self, ParseMatrixError>
+    where
+        F: FnMut(char) -> T,
+    {
+        Self::try_from_str_with(text, |_, _, c| Ok(f(c)))
+    }
+
+    fn try_from_str_with<F>(text: &str, mut f: F) -> Result<Self, ParseMatrixError>
+    where
+        F: FnMut(usize, usize, char) -> Result<T, ParseMatrixError>,
+    {
+        let mut rows = 0;
+        let mut columns = 0;
+        let mut data = vec![];
+
+        for (r, line) in text.lines().enumerate() {
+            let len = line.chars().count();
+
+            if r == 0 {
+                columns = len;
+            } else if len != columns {
+                return Err(ParseMatrixError::Ragged {
+                    row: r,
+                    len,
+                    expected: columns,
+                });
+            }
+
+            for (c, ch) in line.chars().enumerate() {
+                data.push(f(r, c, ch)?);
+            }
+
+            rows += 1;
+        }
+
+        Ok(Matrix {
+            rows,
+            columns,
+            data,
+        })
+    }
+}
+
+impl Matrix<u8> {
+    /// Parses a matrix of single decimal digits, one row per line.
+    pub fn from_digits(text: &str) -> Result<Self, ParseMatrixError> {
+        Self::try_from_str_with(text, |row, column, c| {
+            c.to_digit(10)
+                .map(|d| d as u8)
+                .ok_or(ParseMatrixError::NotADigit { row, column, c })
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn digits() {
+        let m = Matrix::from_digits("123\n456\n").unwrap();
+
+        assert_eq!(m.rows(), 2);
+        assert_eq!(m.columns(), 3);
+        assert_eq!(m.row(1).collect::<Vec<_>>(), vec![&4, &5, &6]);
+    }
+
+    #[test]
+    fn with_mapping() {
+        let m = Matrix::from_str_with("#.\n.#", |c| c == '#').unwrap();
+
+        assert!(m[(0, 0)]);
+        assert!(!m[(0, 1)]);
+        assert!(m[(1, 1)]);
+    }
+
+    #[test]
+    fn errors() {
+        assert_eq!(
+            Matrix::from_digits("123\n456\n78\n9"),
+            Err(ParseMatrixError::Ragged {
+                row: 2,
+                len: 2,
+                expected: 3
+            })
+        );
+        assert_eq!(
+            Matrix::from_digits("12\n3x"),
+            Err(ParseMatrixError::NotADigit {
+                row: 1,
+                column: 1,
+                c: 'x'
+            })
+        );
+    }
+}
@@ -0,0 +1,236 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+
+use crate::{Matrix, Pos};
+
+/// A shortest path found by one of the searches on [Matrix<T>].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Path {
+    /// The total cost of the path. For [Matrix::bfs] this is the number of
+    /// steps.
+    pub cost: u64,
+    /// Every position on the path, from the start it was reached from to
+    /// the goal, both included.
+    pub positions: Vec<(usize, usize)>,
+}
+
+impl<T> Matrix<T> {
+    /// Finds the path with the fewest steps from any of `starts` to a
+    /// position for which `is_goal` returns true. Steps go to the four
+    /// neighbouring cells and are only taken if `can_step(from, to)` returns
+    /// true.
+    pub fn bfs<S, G, P>(&self, starts: S, mut is_goal: G, mut can_step: P) -> Option<Path>
+    where
+        S: IntoIterator<Item = (usize, usize)>,
+        G: FnMut((usize, usize)) -> bool,
+        P: FnMut((usize, usize), (usize, usize)) -> bool,
+    {
+        let mut came_from = Matrix::filled(self.rows, self.columns, None);
+        let mut queue = VecDeque::new();
+
+        for start in starts {
+            if came_from[start].is_none() {
+                came_from[start] = Some(start);
+                queue.push_back((start, 0));
+            }
+        }
+
+        while let Some((pos, steps)) = queue.pop_front() {
+            if is_goal(pos) {
+                return Some(Path {
+                    cost: steps,
+                    positions: trace(&came_from, pos),
+                });
+            }
+
+            for (next, _) in self.neighbors4(pos) {
+                if came_from[next].is_none() && can_step(pos, next) {
+                    came_from[next] = Some(pos);
+                    queue.push_back((next, steps + 1));
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Finds the cheapest path from any of `starts` to a position for which
+    /// `is_goal` returns true. Steps go to the four neighbouring cells, are
+    /// only taken if `can_step(from, to)` returns true, and cost
+    /// `cost(from, to)`.
+    pub fn dijkstra<S, G, P, C>(&self, starts: S, is_goal: G, can_step: P, cost: C) -> Option<Path>
+    where
+        S: IntoIterator<Item = (usize, usize)>,
+        G: FnMut((usize, usize)) -> bool,
+        P: FnMut((usize, usize), (usize, usize)) -> bool,
+        C: FnMut((usize, usize), (usize, usize)) -> u64,
+    {
+        self.best_first(starts, is_goal, can_step, cost, |_| 0)
+    }
+
+    /// Like [Matrix::dijkstra] towards the single position `goal`, but
+    /// explores towards it first using the Manhattan distance as estimate.
+    /// Every step must cost at least 1 for the result to be the cheapest.
+    pub fn astar<S, P, C>(
+        &self,
+        starts: S,
+        goal: (usize, usize),
+        can_step: P,
+        cost: C,
+    ) -> Option<Path>
+    where
+        S: IntoIterator<Item = (usize, usize)>,
+        P: FnMut((usize, usize), (usize, usize)) -> bool,
+        C: FnMut((usize, usize), (usize, usize)) -> u64,
+    {
+        let target = Pos::from(goal);
+
+        self.best_first(
+            starts,
+            |pos| pos == goal,
+            can_step,
+            cost,
+            |pos| Pos::from(pos).manhattan(target) as u64,
+        )
+    }
+
+    fn best_first<S, G, P, C, H>(
+        &self,
+        starts: S,
+        mut is_goal: G,
+        mut can_step: P,
+        mut cost: C,
+        mut heuristic: H,
+    ) -> Option<Path>
+    where
+        S: IntoIterator<Item = (usize, usize)>,
+        G: FnMut((usize, usize)) -> bool,
+        P: FnMut((usize, usize), (usize, usize)) -> bool,
+        C: FnMut((usize, usize), (usize, usize)) -> u64,
+        H: FnMut((usize, usize)) -> u64,
+    {
+        let mut best = Matrix::filled(self.rows, self.columns, u64::MAX);
+        let mut came_from = Matrix::filled(self.rows, self.columns, None);
+        let mut heap = BinaryHeap::new();
+
+        for start in starts {
+            best[start] = 0;
+            came_from[start] = Some(start);
+            heap.push(Reverse((heuristic(start), 0, start)));
+        }
+
+        while let Some(Reverse((_, so_far, pos))) = heap.pop() {
+            if so_far > best[pos] {
+                continue;
+            }
+
+            if is_goal(pos) {
+                return Some(Path {
+                    cost: so_far,
+                    positions: trace(&came_from, pos),
+                });
+            }
+
+            for (next, _) in self.neighbors4(pos) {
+                if !can_step(pos, next) {
+                    continue;
+                }
+
+                let total = so_far + cost(pos, next);
+                if total < best[next] {
+                    best[next] = total;
+                    came_from[next] = Some(pos);
+                    heap.push(Reverse((total + heuristic(next), total, next)));
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Follows `came_from` back from `end` to a start, which is marked by
+/// pointing to itself.
+fn trace(came_from: &Matrix<Option<(usize, usize)>>, end: (usize, usize)) -> Vec<(usize, usize)> {
+    let mut positions = vec![end];
+    let mut pos = end;
+
+    while let Some(prev) = came_from[pos] {
+        if prev == pos {
+            break;
+        }
+        positions.push(prev);
+        pos = prev;
+    }
+
+    positions.reverse();
+
+    positions
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Matrix;
+
+    fn maze() -> Matrix<char> {
+        Matrix::from_str_with("S..#....\n.#.#.##.\n.#...#E.\n.####.#.\n........", |c| c).unwrap()
+    }
+
+    fn find(m: &Matrix<char>, wanted: char) -> (usize, usize) {
+        (0..m.rows())
+            .flat_map(|r| (0..m.columns()).map(move |c| (r, c)))
+            .find(|&p| m[p] == wanted)
+            .unwrap()
+    }
+
+    #[test]
+    fn bfs() {
+        let m = maze();
+        let (start, goal) = (find(&m, 'S'), find(&m, 'E'));
+
+        let path = m.bfs([start], |p| p == goal, |_, to| m[to] != '#').unwrap();
+
+        assert_eq!(path.cost, 14);
+        assert_eq!(path.positions.len(), 15);
+        assert_eq!(path.positions.first(), Some(&start));
+        assert_eq!(path.positions.last(), Some(&goal));
+
+        assert_eq!(m.bfs([start], |p| p == (0, 3), |_, to| m[to] != '#'), None);
+    }
+
+    #[test]
+    fn multi_source() {
+        let m = maze();
+        let goal = find(&m, 'E');
+
+        let path = m
+            .bfs([(0, 0), (4, 7)], |p| p == goal, |_, to| m[to] != '#')
+            .unwrap();
+
+        assert_eq!(path.cost, 3);
+        assert_eq!(path.positions[0], (4, 7));
+    }
+
+    #[test]
+    fn weighted() {
+        let m = Matrix::from_digits("1163751742\n1381373672\n2136511328\n3694931569\n7463417111\n1319128137\n1359912421\n3125421639\n1293138521\n2311944581").unwrap();
+        let goal = (m.rows() - 1, m.columns() - 1);
+        let cost = |_, to| m[to] as u64;
+
+        let d = m
+            .dijkstra([(0, 0)], |p| p == goal, |_, _| true, cost)
+            .unwrap();
+        let a = m.astar([(0, 0)], goal, |_, _| true, cost).unwrap();
+
+        assert_eq!(d.cost, 40);
+        assert_eq!(a.cost, 40);
+        assert_eq!(
+            a.positions
+                .iter()
+                .skip(1)
+                .map(|&p| m[p] as u64)
+                .sum::<u64>(),
+            40
+        );
+    }
+}
@@ -0,0 +1,243 @@
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+use crate::Matrix;
+
+/// One of the eight directions on a grid. Rows grow downwards and columns
+/// grow to the right.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Dir {
+    Up,
+    Down,
+    Left,
+    Right,
+    UpLeft,
+    UpRight,
+    DownLeft,
+    DownRight,
+}
+
+impl Dir {
+    /// The four cardinal directions.
+    pub const CARDINAL: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+    /// The four diagonal directions.
+    pub const DIAGONAL: [Dir; 4] = [Dir::UpRight, Dir::DownRight, Dir::DownLeft, Dir::UpLeft];
+
+    /// All eight directions, clockwise starting from [Dir::Up].
+    pub const ALL: [Dir; 8] = [
+        Dir::Up,
+        Dir::UpRight,
+        Dir::Right,
+        Dir::DownRight,
+        Dir::Down,
+        Dir::DownLeft,
+        Dir::Left,
+        Dir::UpLeft,
+    ];
+
+    /// Returns the change in row and column of a single step.
+    pub fn delta(self) -> (isize, isize) {
+        match self {
+            Dir::Up => (-1, 0),
+            Dir::Down => (1, 0),
+            Dir::Left => (0, -1),
+            Dir::Right => (0, 1),
+            Dir::UpLeft => (-1, -1),
+            Dir::UpRight => (-1, 1),
+            Dir::DownLeft => (1, -1),
+            Dir::DownRight => (1, 1),
+        }
+    }
+
+    /// Returns true for the four diagonal directions.
+    pub fn is_diagonal(self) -> bool {
+        Dir::DIAGONAL.contains(&self)
+    }
+
+    /// Returns the direction rotated by `eighths` of a full turn clockwise.
+    fn rotate(self, eighths: usize) -> Dir {
+        let i = Dir::ALL.iter().position(|&d| d == self).unwrap();
+        Dir::ALL[(i + eighths) % 8]
+    }
+
+    /// Returns the direction after a 90 degree turn counter-clockwise.
+    pub fn turn_left(self) -> Dir {
+        self.rotate(6)
+    }
+
+    /// Returns the direction after a 90 degree turn clockwise.
+    pub fn turn_right(self) -> Dir {
+        self.rotate(2)
+    }
+
+    /// Returns the opposite direction.
+    pub fn reverse(self) -> Dir {
+        self.rotate(4)
+    }
+}
+
+/// A signed position on a grid. Unlike the `(usize, usize)` pairs used to
+/// index a [Matrix<T>], a `Pos` may point outside of it, so stepping off an
+/// edge can be checked afterwards with [Matrix::contains].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Pos {
+    pub row: isize,
+    pub col: isize,
+}
+
+impl Pos {
+    /// Creates a new position.
+    pub fn new(row: isize, col: isize) -> Self {
+        Pos { row, col }
+    }
+
+    /// Returns the position one step away in direction `dir`.
+    pub fn step(self, dir: Dir) -> Self {
+        self + dir
+    }
+
+    /// Returns the Manhattan distance to `other`.
+    pub fn manhattan(self, other: Pos) -> usize {
+        self.row.abs_diff(other.row) + self.col.abs_diff(other.col)
+    }
+
+    /// Returns the position as an index into a grid with the given
+    /// dimensions, or `None` if it lies outside.
+    pub fn within(self, rows: usize, columns: usize) -> Option<(usize, usize)> {
+        let r = usize::try_from(self.row).ok()?;
+        let c = usize::try_from(self.col).ok()?;
+
+        (r < rows && c < columns).then_some((r, c))
+    }
+}
+
+impl From<(usize, usize)> for Pos {
+    fn from((r, c): (usize, usize)) -> Self {
+        Pos::new(r as isize, c as isize)
+    }
+}
+
+impl From<Dir> for Pos {
+    fn from(dir: Dir) -> Self {
+        let (dr, dc) = dir.delta();
+        Pos::new(dr, dc)
+    }
+}
+
+impl Add for Pos {
+    type Output = Pos;
+
+    fn add(self, rhs: Pos) -> Pos {
+        Pos::new(self.row + rhs.row, self.col + rhs.col)
+    }
+}
+
+impl Sub for Pos {
+    type Output = Pos;
+
+    fn sub(self, rhs: Pos) -> Pos {
+        Pos::new(self.row - rhs.row, self.col - rhs.col)
+    }
+}
+
+impl Mul<isize> for Pos {
+    type Output = Pos;
+
+    fn mul(self, rhs: isize) -> Pos {
+        Pos::new(self.row * rhs, self.col * rhs)
+    }
+}
+
+impl Neg for Pos {
+    type Output = Pos;
+
+    fn neg(self) -> Pos {
+        Pos::new(-self.row, -self.col)
+    }
+}
+
+impl Add<Dir> for Pos {
+    type Output = Pos;
+
+    fn add(self, rhs: Dir) -> Pos {
+        self + Pos::from(rhs)
+    }
+}
+
+impl AddAssign for Pos {
+    fn add_assign(&mut self, rhs: Pos) {
+        *self = *self + rhs;
+    }
+}
+
+impl SubAssign for Pos {
+    fn sub_assign(&mut self, rhs: Pos) {
+        *self = *self - rhs;
+    }
+}
+
+impl AddAssign<Dir> for Pos {
+    fn add_assign(&mut self, rhs: Dir) {
+        *self = *self + rhs;
+    }
+}
+
+impl<T> Matrix<T> {
+    /// Returns true if `pos` lies inside the matrix.
+    pub fn contains(&self, pos: Pos) -> bool {
+        self.index_of(pos).is_some()
+    }
+
+    /// Converts `pos` to a `(row, column)` index, or `None` if it lies
+    /// outside the matrix.
+    pub fn index_of(&self, pos: Pos) -> Option<(usize, usize)> {
+        pos.within(self.rows(), self.columns())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn turning() {
+        assert_eq!(Dir::Up.turn_right(), Dir::Right);
+        assert_eq!(Dir::Up.turn_left(), Dir::Left);
+        assert_eq!(Dir::Left.turn_right(), Dir::Up);
+        assert_eq!(Dir::DownLeft.turn_left(), Dir::DownRight);
+        assert_eq!(Dir::UpRight.reverse(), Dir::DownLeft);
+
+        for d in Dir::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.reverse().reverse(), d);
+            assert_eq!(d.turn_right().turn_right(), d.reverse());
+        }
+    }
+
+    #[test]
+    fn arithmetic() {
+        let mut p = Pos::new(2, 3);
+
+        assert_eq!(p + Dir::Up, Pos::new(1, 3));
+        assert_eq!(p.step(Dir::DownLeft), Pos::new(3, 2));
+        assert_eq!(p - Pos::new(5, 5), Pos::new(-3, -2));
+        assert_eq!(p * 2, Pos::new(4, 6));
+        assert_eq!(p.manhattan(Pos::new(-1, 4)), 4);
+
+        p += Dir::Left;
+        p += Dir::Left;
+
+        assert_eq!(p, Pos::new(2, 1));
+    }
+
+    #[test]
+    fn bounds() {
+        let m = Matrix::from_digits("123\n456").unwrap();
+
+        assert_eq!(m.index_of(Pos::new(1, 2)), Some((1, 2)));
+        assert_eq!(m.index_of(Pos::new(2, 0)), None);
+        assert_eq!(m.index_of(Pos::new(0, -1)), None);
+        assert!(m.contains(Pos::from((0, 0)) + Dir::DownRight));
+        assert!(!m.contains(Pos::from((0, 0)) + Dir::UpRight));
+    }
+}
@@ -0,0 +1,180 @@
+use crate::{Dir, Matrix};
+
+impl<T> Matrix<T> {
+    /// Returns an iterator over the cells from `pos` to the edge of the
+    /// matrix in direction `dir`, not including `pos` itself. Panics if `pos`
+    /// is outside the matrix.
+    pub fn ray(&self, pos: (usize, usize), dir: Dir) -> Ray<'_, T> {
+        assert!(
+            pos.0 < self.rows && pos.1 < self.columns,
+            "position {:?} out of range",
+            pos
+        );
+
+        Ray {
+            matrix: self,
+            pos,
+            dir,
+        }
+    }
+}
+
+/// An iterator walking from a cell to the edge of a [Matrix<T>] in a straight
+/// line. Created by [Matrix::ray].
+pub struct Ray<'a, T> {
+    matrix: &'a Matrix<T>,
+    pos: (usize, usize),
+    dir: Dir,
+}
+
+impl<'a, T> Ray<'a, T> {
+    /// Returns true if the ray reaches the edge of the matrix without
+    /// passing a cell for which `blocking` returns true.
+    pub fn reaches_edge<P>(mut self, blocking: P) -> bool
+    where
+        P: FnMut(&T) -> bool,
+    {
+        !self.any(blocking)
+    }
+
+    fn steps_left(&self) -> usize {
+        let (dr, dc) = self.dir.delta();
+        let (r, c) = self.pos;
+
+        let along = |d: isize, i: usize, len: usize| match d {
+            -1 => i,
+            1 => len - i - 1,
+            _ => usize::MAX,
+        };
+
+        along(dr, r, self.matrix.rows()).min(along(dc, c, self.matrix.columns()))
+    }
+}
+
+impl<'a, T> Iterator for Ray<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (dr, dc) = self.dir.delta();
+
+        let r = self.pos.0.checked_add_signed(dr)?;
+        let c = self.pos.1.checked_add_signed(dc)?;
+        let item = self.matrix.get(r, c)?;
+
+        self.pos = (r, c);
+
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.steps_left();
+        (len, Some(len))
+    }
+}
+
+impl<T> ExactSizeIterator for Ray<'_, T> {}
+
+/// Extension trait for iterators looking along a line of sight.
+pub trait TakeUntilBlocking: Iterator + Sized {
+    /// Yields elements up to and including the first one for which
+    /// `blocking` returns true. Counting the result gives the viewing
+    /// distance from the start of the iterator.
+    fn take_until_blocking<P>(self, blocking: P) -> TakeUntilBlockingIter<Self, P>
+    where
+        P: FnMut(&Self::Item) -> bool,
+    {
+        TakeUntilBlockingIter {
+            inner: self,
+            blocking,
+            done: false,
+        }
+    }
+}
+
+impl<I: Iterator> TakeUntilBlocking for I {}
+
+/// Iterator returned by [TakeUntilBlocking::take_until_blocking].
+pub struct TakeUntilBlockingIter<I, P> {
+    inner: I,
+    blocking: P,
+    done: bool,
+}
+
+impl<I, P> Iterator for TakeUntilBlockingIter<I, P>
+where
+    I: Iterator,
+    P: FnMut(&I::Item) -> bool,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let item = self.inner.next()?;
+        if (self.blocking)(&item) {
+            self.done = true;
+        }
+
+        Some(item)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trees() -> Matrix<u8> {
+        Matrix::from_digits("30373\n25512\n65332\n33549\n35390").unwrap()
+    }
+
+    #[test]
+    fn rays() {
+        let m = trees();
+
+        assert_eq!(m.ray((1, 2), Dir::Up).collect::<Vec<_>>(), vec![&3]);
+        assert_eq!(m.ray((1, 2), Dir::Left).collect::<Vec<_>>(), vec![&5, &2]);
+        assert_eq!(
+            m.ray((1, 2), Dir::Down).collect::<Vec<_>>(),
+            vec![&3, &5, &3]
+        );
+        assert_eq!(m.ray((1, 2), Dir::Right).len(), 2);
+        assert_eq!(m.ray((0, 0), Dir::Up).len(), 0);
+        assert_eq!(
+            m.ray((2, 2), Dir::DownRight).collect::<Vec<_>>(),
+            vec![&4, &0]
+        );
+        assert_eq!(
+            m.ray((2, 2), Dir::UpRight).collect::<Vec<_>>(),
+            vec![&1, &3]
+        );
+        assert_eq!(m.ray((4, 1), Dir::UpLeft).len(), 1);
+    }
+
+    #[test]
+    fn blocking() {
+        let m = trees();
+        let height = m[(3, 2)];
+
+        let distances: Vec<usize> = Dir::CARDINAL
+            .iter()
+            .map(|&d| {
+                m.ray((3, 2), d)
+                    .take_until_blocking(|h| **h >= height)
+                    .count()
+            })
+            .collect();
+
+        assert_eq!(distances, vec![2, 2, 1, 2]);
+
+        assert!(m.ray((1, 1), Dir::Up).reaches_edge(|h| *h >= m[(1, 1)]));
+        assert!(!m.ray((1, 1), Dir::Right).reaches_edge(|h| *h >= m[(1, 1)]));
+    }
+
+    #[test]
+    #[should_panic(expected = "position (5, 0) out of range")]
+    fn outside() {
+        trees().ray((5, 0), Dir::Up);
+    }
+}
@@ -0,0 +1,266 @@
+use std::collections::HashSet;
+use std::io::{self, Write};
+
+use crate::Matrix;
+
+/// A terminal colour used to highlight cells when rendering.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    fn ansi(self) -> &'static str {
+        match self {
+            Color::Red => "\x1b[31m",
+            Color::Green => "\x1b[32m",
+            Color::Yellow => "\x1b[33m",
+            Color::Blue => "\x1b[34m",
+            Color::Magenta => "\x1b[35m",
+            Color::Cyan => "\x1b[36m",
+        }
+    }
+}
+
+const ANSI_RESET: &str = "\x1b[0m";
+
+impl<T> Matrix<T> {
+    /// Returns the matrix as text, one line per row, mapping each cell to a
+    /// character with `f`.
+    pub fn render<F>(&self, f: F) -> String
+    where
+        F: FnMut(&T) -> char,
+    {
+        self.render_highlighted(f, [], Color::Red)
+    }
+
+    /// Like [Matrix::render], but wraps the cells at `positions` in ANSI
+    /// escape codes for `color`.
+    pub fn render_highlighted<F, I>(&self, mut f: F, positions: I, color: Color) -> String
+    where
+        F: FnMut(&T) -> char,
+        I: IntoIterator<Item = (usize, usize)>,
+    {
+        let highlighted: HashSet<(usize, usize)> = positions.into_iter().collect();
+        let mut s = String::new();
+
+        for r in 0..self.rows() {
+            for (c, cell) in self.row(r).enumerate() {
+                if highlighted.contains(&(r, c)) {
+                    s.push_str(color.ansi());
+                    s.push(f(cell));
+                    s.push_str(ANSI_RESET);
+                } else {
+                    s.push(f(cell));
+                }
+            }
+            s.push('\n');
+        }
+
+        s
+    }
+
+    /// Returns a heatmap image with one pixel per cell. `value` maps each
+    /// cell to a number, and the smallest and largest numbers become the
+    /// coldest and hottest colours.
+    pub fn heatmap<F>(&self, value: F) -> Image
+    where
+        F: Fn(&T) -> f64,
+    {
+        let values: Vec<f64> = self.data.iter().map(value).collect();
+        let min = values.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let range = if max > min { max - min } else { 1.0 };
+
+        Image {
+            width: self.columns(),
+            height: self.rows(),
+            pixels: values.iter().map(|v| heat((v - min) / range)).collect(),
+        }
+    }
+}
+
+/// Maps `t` in `0.0..=1.0` to a colour going from black through red and
+/// yellow to white.
+fn heat(t: f64) -> [u8; 3] {
+    let channel = |x: f64| (x.clamp(0.0, 1.0) * 255.0).round() as u8;
+
+    [
+        channel(t * 3.0),
+        channel(t * 3.0 - 1.0),
+        channel(t * 3.0 - 2.0),
+    ]
+}
+
+/// An RGB image, as produced by [Matrix::heatmap].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Image {
+    width: usize,
+    height: usize,
+    pixels: Vec<[u8; 3]>,
+}
+
+impl Image {
+    /// Returns the width in pixels.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the height in pixels.
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Returns the colour of the pixel at column `x` and row `y`.
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 3] {
+        self.pixels[y * self.width + x]
+    }
+
+    /// Returns a copy where every pixel has become a `factor` by `factor`
+    /// square, since one pixel per cell is usually too small to look at.
+    pub fn scale(&self, factor: usize) -> Image {
+        let width = self.width * factor;
+        let height = self.height * factor;
+        let pixels = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| self.pixel(x / factor, y / factor))
+            .collect();
+
+        Image {
+            width,
+            height,
+            pixels,
+        }
+    }
+
+    /// Writes the image as a binary PPM (`P6`) file.
+    pub fn write_ppm<W: Write>(&self, mut w: W) -> io::Result<()> {
+        write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+        w.write_all(&self.pixels.concat())
+    }
+
+    /// Writes the image as a PNG file. The image data is stored without
+    /// compression, which keeps the encoder small at the cost of file size.
+    pub fn write_png<W: Write>(&self, mut w: W) -> io::Result<()> {
+        let mut raw = Vec::with_capacity((self.width * 3 + 1) * self.height);
+        for row in self.pixels.chunks(self.width.max(1)).take(self.height) {
+            raw.push(0);
+            raw.extend(row.iter().flatten());
+        }
+
+        let mut ihdr = vec![];
+        ihdr.extend((self.width as u32).to_be_bytes());
+        ihdr.extend((self.height as u32).to_be_bytes());
+        ihdr.extend([8, 2, 0, 0, 0]);
+
+        w.write_all(b"\x89PNG\r\n\x1a\n")?;
+        write_chunk(&mut w, b"IHDR", &ihdr)?;
+        write_chunk(&mut w, b"IDAT", &zlib_stored(&raw))?;
+        write_chunk(&mut w, b"IEND", &[])
+    }
+}
+
+fn write_chunk<W: Write>(w: &mut W, kind: &[u8; 4], data: &[u8]) -> io::Result<()> {
+    w.write_all(&(data.len() as u32).to_be_bytes())?;
+    w.write_all(kind)?;
+    w.write_all(data)?;
+
+    let crc = crc32(kind.iter().chain(data));
+    w.write_all(&crc.to_be_bytes())
+}
+
+/// Wraps `data` in a zlib stream made of uncompressed deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut out = vec![0x78, 0x01];
+
+    let mut blocks = data.chunks(u16::MAX as usize).peekable();
+    if blocks.peek().is_none() {
+        out.extend([1, 0, 0, 0xff, 0xff]);
+    }
+    while let Some(block) = blocks.next() {
+        let last = blocks.peek().is_none();
+        let len = block.len() as u16;
+
+        out.push(last as u8);
+        out.extend(len.to_le_bytes());
+        out.extend((!len).to_le_bytes());
+        out.extend(block);
+    }
+
+    out.extend(adler32(data).to_be_bytes());
+
+    out
+}
+
+fn crc32<'a>(bytes: impl IntoIterator<Item = &'a u8>) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= b as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+
+    !crc
+}
+
+fn adler32(bytes: &[u8]) -> u32 {
+    let (mut a, mut b) = (1u32, 0u32);
+    for &byte in bytes {
+        a = (a + byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+
+    (b << 16) | a
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text() {
+        let m = Matrix::from_digits("012\n345").unwrap();
+
+        assert_eq!(m.render(|d| if *d > 2 { '#' } else { '.' }), "...\n###\n");
+        assert_eq!(
+            m.render_highlighted(|d| (b'0' + d) as char, [(0, 1)], Color::Green),
+            "0\x1b[32m1\x1b[0m2\n345\n"
+        );
+    }
+
+    #[test]
+    fn heatmap() {
+        let m = Matrix::from_digits("09\n99").unwrap();
+        let image = m.heatmap(|d| *d as f64);
+
+        assert_eq!(image.pixel(0, 0), [0, 0, 0]);
+        assert_eq!(image.pixel(1, 1), [255, 255, 255]);
+
+        let mut ppm = vec![];
+        image.scale(2).write_ppm(&mut ppm).unwrap();
+
+        assert!(ppm.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(ppm.len(), 11 + 4 * 4 * 3);
+    }
+
+    #[test]
+    fn png() {
+        let m = Matrix::from_digits("0123\n4567").unwrap();
+        let mut png = vec![];
+        m.heatmap(|d| *d as f64).write_png(&mut png).unwrap();
+
+        assert!(png.starts_with(b"\x89PNG\r\n\x1a\n"));
+        assert!(png.ends_with(&[0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]));
+        assert_eq!(adler32(b"Wikipedia"), 0x11e6_0398);
+    }
+}
@@ -0,0 +1,283 @@
+use std::collections::BTreeMap;
+
+use crate::Matrix;
+
+/// The smallest rectangle containing every occupied cell of a
+/// [SparseGrid<T>]. Both corners are inclusive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Bounds {
+    pub min: (i64, i64),
+    pub max: (i64, i64),
+}
+
+impl Bounds {
+    fn point(pos: (i64, i64)) -> Self {
+        Bounds { min: pos, max: pos }
+    }
+
+    fn extend(&mut self, (r, c): (i64, i64)) {
+        self.min = (self.min.0.min(r), self.min.1.min(c));
+        self.max = (self.max.0.max(r), self.max.1.max(c));
+    }
+
+    fn on_edge(&self, (r, c): (i64, i64)) -> bool {
+        r == self.min.0 || r == self.max.0 || c == self.min.1 || c == self.max.1
+    }
+
+    /// Returns the number of rows spanned.
+    pub fn rows(&self) -> usize {
+        (self.max.0 - self.min.0) as usize + 1
+    }
+
+    /// Returns the number of columns spanned.
+    pub fn columns(&self) -> usize {
+        (self.max.1 - self.min.1) as usize + 1
+    }
+
+    /// Returns true if `pos` lies inside the bounds.
+    pub fn contains(&self, (r, c): (i64, i64)) -> bool {
+        (self.min.0..=self.max.0).contains(&r) && (self.min.1..=self.max.1).contains(&c)
+    }
+}
+
+/// An unbounded grid that only stores occupied cells, keyed by signed
+/// `(row, column)` positions.
+///
+/// Cells are kept in row-major order, and the bounding box of the occupied
+/// cells is tracked as cells are inserted and removed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SparseGrid<T> {
+    cells: BTreeMap<(i64, i64), T>,
+    bounds: Option<Bounds>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        SparseGrid {
+            cells: BTreeMap::new(),
+            bounds: None,
+        }
+    }
+}
+
+impl<T> SparseGrid<T> {
+    /// Creates an empty grid.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of occupied cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    /// Returns true if no cells are occupied.
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Returns the bounding box of the occupied cells, or `None` if the grid
+    /// is empty.
+    pub fn bounds(&self) -> Option<Bounds> {
+        self.bounds
+    }
+
+    /// Returns a reference to the value at `pos`, if occupied.
+    pub fn get(&self, pos: (i64, i64)) -> Option<&T> {
+        self.cells.get(&pos)
+    }
+
+    /// Returns a mutable reference to the value at `pos`, if occupied.
+    pub fn get_mut(&mut self, pos: (i64, i64)) -> Option<&mut T> {
+        self.cells.get_mut(&pos)
+    }
+
+    /// Returns true if `pos` is occupied.
+    pub fn contains(&self, pos: (i64, i64)) -> bool {
+        self.cells.contains_key(&pos)
+    }
+
+    /// Stores `value` at `pos`, returning the previous value if the cell was
+    /// already occupied.
+    pub fn insert(&mut self, pos: (i64, i64), value: T) -> Option<T> {
+        match &mut self.bounds {
+            Some(b) => b.extend(pos),
+            None => self.bounds = Some(Bounds::point(pos)),
+        }
+
+        self.cells.insert(pos, value)
+    }
+
+    /// Clears the cell at `pos`, returning its value if it was occupied.
+    pub fn remove(&mut self, pos: (i64, i64)) -> Option<T> {
+        let value = self.cells.remove(&pos)?;
+
+        if self.bounds.is_some_and(|b| b.on_edge(pos)) {
+            self.bounds = self.compute_bounds();
+        }
+
+        Some(value)
+    }
+
+    fn compute_bounds(&self) -> Option<Bounds> {
+        let mut positions = self.cells.keys();
+        let mut bounds = Bounds::point(*positions.next()?);
+
+        for &pos in positions {
+            bounds.extend(pos);
+        }
+
+        Some(bounds)
+    }
+
+    /// Returns an iterator over the occupied cells in row-major order.
+    pub fn iter(&self) -> impl Iterator<Item = ((i64, i64), &T)> {
+        self.cells.iter().map(|(&pos, v)| (pos, v))
+    }
+
+    /// Returns the occupied region as text, one line per row, mapping each
+    /// cell (or `None` for an empty one) to a character with `f`.
+    pub fn render<F>(&self, mut f: F) -> String
+    where
+        F: FnMut(Option<&T>) -> char,
+    {
+        let mut s = String::new();
+
+        if let Some(b) = self.bounds {
+            for r in b.min.0..=b.max.0 {
+                for c in b.min.1..=b.max.1 {
+                    s.push(f(self.get((r, c))));
+                }
+                s.push('\n');
+            }
+        }
+
+        s
+    }
+}
+
+impl<T: Clone> SparseGrid<T> {
+    /// Copies the occupied region into a dense matrix, using `fill` for
+    /// empty cells. Position [Bounds::min] becomes `(0, 0)` in the matrix.
+    pub fn to_matrix(&self, fill: T) -> Matrix<T> {
+        let Some(b) = self.bounds else {
+            return Matrix {
+                rows: 0,
+                columns: 0,
+                data: vec![],
+            };
+        };
+
+        let mut data = vec![fill; b.rows() * b.columns()];
+        for (&(r, c), v) in &self.cells {
+            let i = (r - b.min.0) as usize * b.columns() + (c - b.min.1) as usize;
+            data[i] = v.clone();
+        }
+
+        Matrix {
+            rows: b.rows(),
+            columns: b.columns(),
+            data,
+        }
+    }
+}
+
+impl<T> From<Matrix<T>> for SparseGrid<T> {
+    /// Turns every cell of the matrix into an occupied cell, with `(0, 0)`
+    /// at the top left.
+    fn from(m: Matrix<T>) -> Self {
+        let columns = m.columns;
+
+        m.data
+            .into_iter()
+            .enumerate()
+            .map(|(i, v)| (((i / columns) as i64, (i % columns) as i64), v))
+            .collect()
+    }
+}
+
+impl<T> FromIterator<((i64, i64), T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = ((i64, i64), T)>>(iter: I) -> Self {
+        let mut grid = SparseGrid::new();
+        for (pos, v) in iter {
+            grid.insert(pos, v);
+        }
+
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        let mut g = SparseGrid::new();
+
+        assert_eq!(g.bounds(), None);
+
+        g.insert((0, 0), 'a');
+        g.insert((-2, 3), 'b');
+        g.insert((1, -1), 'c');
+
+        assert_eq!(
+            g.bounds(),
+            Some(Bounds {
+                min: (-2, -1),
+                max: (1, 3)
+            })
+        );
+
+        g.remove((-2, 3));
+
+        assert_eq!(
+            g.bounds(),
+            Some(Bounds {
+                min: (0, -1),
+                max: (1, 0)
+            })
+        );
+
+        g.remove((0, 0));
+        g.remove((1, -1));
+
+        assert!(g.is_empty());
+        assert_eq!(g.bounds(), None);
+    }
+
+    #[test]
+    fn row_major() {
+        let g: SparseGrid<u8> = [((1, 0), 3), ((-1, 5), 1), ((1, -3), 2), ((-1, -1), 0)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(
+            g.iter().map(|(_, v)| *v).collect::<Vec<_>>(),
+            vec![0, 1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn render() {
+        let g: SparseGrid<char> = [((-1, -1), '#'), ((0, 1), 'o')].into_iter().collect();
+
+        assert_eq!(g.render(|c| c.copied().unwrap_or('.')), "#..\n..o\n");
+    }
+
+    #[test]
+    fn matrix_round_trip() {
+        let m = Matrix::from_digits("120\n034").unwrap();
+        let mut g = SparseGrid::from(m.clone());
+
+        assert_eq!(g.len(), 6);
+        assert_eq!(g.to_matrix(0), m);
+
+        g.insert((-1, 3), 9);
+
+        assert_eq!(
+            g.to_matrix(0),
+            Matrix::from_digits("0009\n1200\n0340").unwrap()
+        );
+    }
+}
@@ -0,0 +1,329 @@
+use std::ops::{Index, Range};
+
+use crate::Matrix;
+
+/// A borrowed, possibly transformed, rectangular window into a [Matrix<T>].
+///
+/// Views never copy the underlying data. Transposing, rotating and flipping
+/// only change how positions in the view map to positions in the matrix, so
+/// they can be chained freely. Use [View::to_matrix] to get an owned copy.
+#[derive(Debug)]
+pub struct View<'a, T> {
+    data: &'a [T],
+    offset: usize,
+    rows: usize,
+    columns: usize,
+    row_stride: isize,
+    col_stride: isize,
+}
+
+impl<T> Clone for View<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for View<'_, T> {}
+
+impl<T> Matrix<T> {
+    /// Returns a view of the whole matrix.
+    pub fn as_view(&self) -> View<'_, T> {
+        View {
+            data: &self.data,
+            offset: 0,
+            rows: self.rows,
+            columns: self.columns,
+            row_stride: self.columns as isize,
+            col_stride: 1,
+        }
+    }
+
+    /// Returns a view of the cells in the given row and column ranges.
+    /// Panics if the ranges reach outside the matrix.
+    pub fn view(&self, rows: Range<usize>, cols: Range<usize>) -> View<'_, T> {
+        self.as_view().view(rows, cols)
+    }
+
+    /// Returns a view with rows and columns swapped.
+    pub fn transpose(&self) -> View<'_, T> {
+        self.as_view().transpose()
+    }
+
+    /// Returns a view rotated a quarter turn clockwise.
+    pub fn rotate_cw(&self) -> View<'_, T> {
+        self.as_view().rotate_cw()
+    }
+
+    /// Returns a view rotated a quarter turn counter-clockwise.
+    pub fn rotate_ccw(&self) -> View<'_, T> {
+        self.as_view().rotate_ccw()
+    }
+
+    /// Returns a view mirrored left to right.
+    pub fn flip_h(&self) -> View<'_, T> {
+        self.as_view().flip_h()
+    }
+
+    /// Returns a view mirrored top to bottom.
+    pub fn flip_v(&self) -> View<'_, T> {
+        self.as_view().flip_v()
+    }
+}
+
+impl<'a, T> View<'a, T> {
+    /// Returns the number of rows.
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    /// Returns the number of columns.
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    fn index_of(&self, r: usize, c: usize) -> usize {
+        (self.offset as isize + r as isize * self.row_stride + c as isize * self.col_stride)
+            as usize
+    }
+
+    /// Returns a reference to the element at row `r` and column `c` of the
+    /// view, or `None` if the position is outside it.
+    pub fn get(&self, r: usize, c: usize) -> Option<&'a T> {
+        if r < self.rows && c < self.columns {
+            Some(&self.data[self.index_of(r, c)])
+        } else {
+            None
+        }
+    }
+
+    /// Returns an iterator over the elements of row `r`, from left to right.
+    /// Panics if the row is out of range.
+    pub fn row(&self, r: usize) -> LineIter<'a, T> {
+        assert!(r < self.rows, "row {} out of range", r);
+
+        LineIter {
+            data: self.data,
+            start: self.index_of(r, 0),
+            stride: self.col_stride,
+            front: 0,
+            back: self.columns,
+        }
+    }
+
+    /// Returns an iterator over the elements of column `c`, from top to
+    /// bottom. Panics if the column is out of range.
+    pub fn column(&self, c: usize) -> LineIter<'a, T> {
+        assert!(c < self.columns, "column {} out of range", c);
+
+        LineIter {
+            data: self.data,
+            start: self.index_of(0, c),
+            stride: self.row_stride,
+            front: 0,
+            back: self.rows,
+        }
+    }
+
+    /// Returns a view of the cells in the given row and column ranges of
+    /// this view. Panics if the ranges reach outside the view.
+    pub fn view(self, rows: Range<usize>, cols: Range<usize>) -> Self {
+        assert!(
+            rows.start <= rows.end && rows.end <= self.rows,
+            "rows {:?} out of range",
+            rows
+        );
+        assert!(
+            cols.start <= cols.end && cols.end <= self.columns,
+            "columns {:?} out of range",
+            cols
+        );
+
+        let offset = if rows.is_empty() || cols.is_empty() {
+            self.offset
+        } else {
+            self.index_of(rows.start, cols.start)
+        };
+
+        View {
+            offset,
+            rows: rows.len(),
+            columns: cols.len(),
+            ..self
+        }
+    }
+
+    /// Returns a view with rows and columns swapped.
+    pub fn transpose(self) -> Self {
+        View {
+            rows: self.columns,
+            columns: self.rows,
+            row_stride: self.col_stride,
+            col_stride: self.row_stride,
+            ..self
+        }
+    }
+
+    /// Returns a view mirrored left to right.
+    pub fn flip_h(self) -> Self {
+        if self.columns == 0 {
+            return self;
+        }
+
+        View {
+            offset: self.index_of(0, self.columns - 1),
+            col_stride: -self.col_stride,
+            ..self
+        }
+    }
+
+    /// Returns a view mirrored top to bottom.
+    pub fn flip_v(self) -> Self {
+        if self.rows == 0 {
+            return self;
+        }
+
+        View {
+            offset: self.index_of(self.rows - 1, 0),
+            row_stride: -self.row_stride,
+            ..self
+        }
+    }
+
+    /// Returns a view rotated a quarter turn clockwise.
+    pub fn rotate_cw(self) -> Self {
+        self.transpose().flip_h()
+    }
+
+    /// Returns a view rotated a quarter turn counter-clockwise.
+    pub fn rotate_ccw(self) -> Self {
+        self.transpose().flip_v()
+    }
+}
+
+impl<T: Clone> View<'_, T> {
+    /// Copies the view into a new matrix.
+    pub fn to_matrix(&self) -> Matrix<T> {
+        let data = (0..self.rows).flat_map(|r| self.row(r).cloned()).collect();
+
+        Matrix {
+            rows: self.rows,
+            columns: self.columns,
+            data,
+        }
+    }
+}
+
+impl<T> Index<(usize, usize)> for View<'_, T> {
+    type Output = T;
+
+    fn index(&self, (r, c): (usize, usize)) -> &T {
+        self.get(r, c).expect("valid position")
+    }
+}
+
+/// An iterator over a single row or column of a [View].
+pub struct LineIter<'a, T> {
+    data: &'a [T],
+    start: usize,
+    stride: isize,
+    front: usize,
+    back: usize,
+}
+
+impl<T> LineIter<'_, T> {
+    fn index_of(&self, i: usize) -> usize {
+        (self.start as isize + i as isize * self.stride) as usize
+    }
+}
+
+impl<'a, T> Iterator for LineIter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        let item = &self.data[self.index_of(self.front)];
+        self.front += 1;
+
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T> DoubleEndedIterator for LineIter<'_, T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.front == self.back {
+            return None;
+        }
+
+        self.back -= 1;
+
+        Some(&self.data[self.index_of(self.back)])
+    }
+}
+
+impl<T> ExactSizeIterator for LineIter<'_, T> {}
+
+#[cfg(test)]
+mod tests {
+    use crate::Matrix;
+
+    fn two_by_three() -> Matrix<u8> {
+        Matrix::from_digits("123\n456").unwrap()
+    }
+
+    #[test]
+    fn transforms() {
+        let m = two_by_three();
+
+        assert_eq!(
+            m.transpose().to_matrix(),
+            Matrix::from_digits("14\n25\n36").unwrap()
+        );
+        assert_eq!(
+            m.rotate_cw().to_matrix(),
+            Matrix::from_digits("41\n52\n63").unwrap()
+        );
+        assert_eq!(
+            m.rotate_ccw().to_matrix(),
+            Matrix::from_digits("36\n25\n14").unwrap()
+        );
+        assert_eq!(
+            m.flip_h().to_matrix(),
+            Matrix::from_digits("321\n654").unwrap()
+        );
+        assert_eq!(
+            m.flip_v().to_matrix(),
+            Matrix::from_digits("456\n123").unwrap()
+        );
+
+        let v = m.rotate_cw().rotate_cw().rotate_cw().rotate_cw();
+        assert_eq!(v.to_matrix(), m);
+        assert_eq!(
+            m.rotate_cw().rotate_cw().to_matrix(),
+            m.flip_h().flip_v().to_matrix()
+        );
+    }
+
+    #[test]
+    fn views() {
+        let m = Matrix::from_digits("1234\n5678\n9012").unwrap();
+        let v = m.view(1..3, 1..4);
+
+        assert_eq!(v.rows(), 2);
+        assert_eq!(v.columns(), 3);
+        assert_eq!(v[(0, 0)], 6);
+        assert_eq!(v.get(2, 0), None);
+        assert_eq!(v.row(1).collect::<Vec<_>>(), vec![&0, &1, &2]);
+        assert_eq!(v.column(2).rev().collect::<Vec<_>>(), vec![&2, &8]);
+
+        let r = v.rotate_ccw().view(0..2, 1..2);
+        assert_eq!(r.to_matrix(), Matrix::from_digits("2\n1").unwrap());
+    }
+}
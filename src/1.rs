@@ -1,20 +1,189 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::io::BufRead;
+
+use aoc2022::parse::ParseError;
+
+/// The `k` elves carrying the most calories seen so far, kept in a min-heap
+/// so each new elf costs at most `O(log k)`. Among elves with the same total
+/// the later one is the smallest, so the earlier one is kept.
+#[derive(Debug)]
+struct TopK {
+    k: usize,
+    heap: BinaryHeap<Reverse<(u64, Reverse<usize>)>>,
+}
+
+impl TopK {
+    fn new(k: usize) -> Self {
+        TopK {
+            k,
+            heap: BinaryHeap::with_capacity(k + 1),
+        }
+    }
+
+    fn push(&mut self, elf: usize, total: u64) {
+        if self.heap.len() < self.k {
+            self.heap.push(Reverse((total, Reverse(elf))));
+        } else if let Some(mut smallest) = self.heap.peek_mut() {
+            if total > smallest.0 .0 {
+                *smallest = Reverse((total, Reverse(elf)));
+            }
+        }
+    }
+
+    /// Returns `(elf, total)` for the top elves, most calories first. Ties
+    /// are listed in input order.
+    fn into_sorted(self) -> Vec<(usize, u64)> {
+        let mut elves: Vec<(usize, u64)> = self
+            .heap
+            .into_iter()
+            .map(|Reverse((total, Reverse(elf)))| (elf, total))
+            .collect();
+
+        elves.sort_by_key(|&(elf, total)| (Reverse(total), elf));
+
+        elves
+    }
+}
+
+/// Reads the food of each elf from `input` and calls `f` with each elf's
+/// number, counting from 1, and total, one line at a time.
+fn elf_totals<R, F>(mut input: R, mut f: F) -> Result<(), ParseError>
+where
+    R: BufRead,
+    F: FnMut(usize, u64),
+{
+    let mut line = String::new();
+    let mut line_no = 0;
+    let mut elf = 1;
+    let mut total = None;
+
+    loop {
+        let read = input.read_line(&mut line).map_err(|e| {
+            ParseError::new(1, format!("cannot read input: {}", e)).offset_lines(line_no)
+        })?;
+        if read == 0 {
+            break;
+        }
+
+        let food = line.trim_end();
+
+        if food.is_empty() {
+            if let Some(t) = total.take() {
+                f(elf, t);
+                elf += 1;
+            }
+        } else {
+            let calories = food.parse::<u64>().map_err(|e| {
+                let mut err = ParseError::new(1, format!("cannot parse {:?}: {}", food, e))
+                    .offset_lines(line_no);
+                err.line = food.to_owned();
+                err
+            })?;
+
+            *total.get_or_insert(0) += calories;
+        }
+
+        line.clear();
+        line_no += 1;
+    }
+
+    if let Some(t) = total {
+        f(elf, t);
+    }
+
+    Ok(())
+}
+
 fn main() {
-    let contents = std::fs::read_to_string("inputs/1.txt").expect("read input");
+    let mut k = 3;
+    let mut path = None;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--top" {
+            k = args
+                .next()
+                .and_then(|n| n.parse().ok())
+                .expect("--top needs a number");
+        } else {
+            path = Some(arg);
+        }
+    }
+
+    let mut top = TopK::new(k);
+
+    let input = aoc2022::open_input(1, path).unwrap_or_else(|e| aoc2022::exit_with_error(1, e));
+
+    if let Err(e) = elf_totals(input, |elf, total| top.push(elf, total)) {
+        aoc2022::exit_with_error(1, e);
+    }
+
+    let elves = top.into_sorted();
+
+    if let Some((_, max)) = elves.first() {
+        println!("max: {}", max);
+    }
+
+    for (elf, total) in &elves {
+        println!("elf {}: {}", elf, total);
+    }
+
+    let sum: u64 = elves.iter().map(|(_, total)| total).sum();
+
+    if elves.len() < k {
+        println!("top {} (only {} elves): {}", k, elves.len(), sum);
+    } else {
+        println!("top {}: {}", k, sum);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "1000\n2000\n3000\n\n4000\n\n5000\n6000\n\n7000\n8000\n9000\n\n10000\n";
+
+    fn top(input: &str, k: usize) -> Result<Vec<(usize, u64)>, ParseError> {
+        let mut top = TopK::new(k);
+        elf_totals(input.as_bytes(), |elf, total| top.push(elf, total))?;
+
+        Ok(top.into_sorted())
+    }
+
+    #[test]
+    fn top_k() {
+        assert_eq!(
+            top(EXAMPLE, 3),
+            Ok(vec![(4, 24000), (3, 11000), (5, 10000)])
+        );
+        assert_eq!(top(EXAMPLE, 1), Ok(vec![(4, 24000)]));
+        assert_eq!(top(EXAMPLE, 0), Ok(vec![]));
+        assert_eq!(
+            top("5\n\n7\n\n5\n\n7\n", 3),
+            Ok(vec![(2, 7), (4, 7), (1, 5)])
+        );
+    }
 
-    let mut elves: Vec<i32> = contents
-        .split("\n\n")
-        .map(|elf| {
-            elf.split("\n")
-                .map(|food| food.parse::<i32>().ok().unwrap_or(0))
-                .sum()
-        })
-        .collect();
+    #[test]
+    fn fewer_than_k() {
+        assert_eq!(top("1\n2\n\n\n3\n", 3), Ok(vec![(1, 3), (2, 3)]));
+        assert_eq!(top("", 3), Ok(vec![]));
+    }
 
-    elves.sort();
+    #[test]
+    fn malformed() {
+        let e = top("1000\n\n20x0\n", 3).unwrap_err();
 
-    println!("max: {}", elves.last().unwrap());
+        assert_eq!(e.line_no, 3);
+        assert_eq!(e.line, "20x0");
+    }
 
-    let max_three: i32 = elves.iter().skip(elves.len() - 3).sum();
+    #[test]
+    fn unreadable() {
+        let e = elf_totals(&b"1000\n\n\xff\n"[..], |_, _| ()).unwrap_err();
 
-    println!("max three: {}", max_three);
+        assert_eq!(e.line_no, 3);
+        assert!(e.reason.starts_with("cannot read input: "));
+    }
 }
@@ -1,103 +1,512 @@
-#[derive(Clone, Copy)]
+use aoc2022::parse::{parse_lines, ParseError};
+
+#[derive(Clone, Copy, Debug)]
 enum Call {
     Rock,
     Paper,
     Scissors,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum Response {
     Rock,
     Paper,
     Scissors,
 }
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
 enum DesiredOutcome {
     Lose,
     Draw,
     Win,
 }
 
-impl From<&str> for Call {
-    fn from(c: &str) -> Call {
+/// The second column of the strategy guide, before deciding what it means.
+#[derive(Clone, Copy, Debug)]
+enum Column {
+    X,
+    Y,
+    Z,
+}
+
+impl TryFrom<&str> for Column {
+    type Error = ParseError;
+
+    fn try_from(c: &str) -> Result<Column, ParseError> {
         match c {
-            "A" => Call::Rock,
-            "B" => Call::Paper,
-            "C" => Call::Scissors,
-            _ => panic!("lol char"),
+            "X" => Ok(Column::X),
+            "Y" => Ok(Column::Y),
+            "Z" => Ok(Column::Z),
+            _ => Err(ParseError::new(1, format!("unknown column {:?}", c))),
         }
     }
 }
 
-impl From<&str> for Response {
-    fn from(c: &str) -> Response {
+impl TryFrom<&str> for Call {
+    type Error = ParseError;
+
+    fn try_from(c: &str) -> Result<Call, ParseError> {
         match c {
-            "X" => Response::Rock,
-            "Y" => Response::Paper,
-            "Z" => Response::Scissors,
-            _ => panic!("lol char"),
+            "A" => Ok(Call::Rock),
+            "B" => Ok(Call::Paper),
+            "C" => Ok(Call::Scissors),
+            _ => Err(ParseError::new(1, format!("unknown call {:?}", c))),
         }
     }
 }
-impl From<&str> for DesiredOutcome {
-    fn from(c: &str) -> DesiredOutcome {
-        match c {
-            "X" => DesiredOutcome::Lose,
-            "Y" => DesiredOutcome::Draw,
-            "Z" => DesiredOutcome::Win,
-            _ => panic!("lol char"),
+
+/// Parses a line of the strategy guide into its two columns.
+fn parse_game<A, B>(game: &str) -> Result<(A, B), ParseError>
+where
+    A: for<'a> TryFrom<&'a str, Error = ParseError>,
+    B: for<'a> TryFrom<&'a str, Error = ParseError>,
+{
+    let (a, b) = game
+        .split_once(' ')
+        .ok_or_else(|| ParseError::new(1, "expected two columns"))?;
+
+    Ok((
+        A::try_from(a)?,
+        B::try_from(b).map_err(|e| e.offset_columns(a.len() + 1))?,
+    ))
+}
+
+/// A gesture in a [Rules] set, identified by its position in the cyclic
+/// order.
+type Gesture = usize;
+
+/// A rock-paper-scissors style game with an odd number of gestures in a
+/// cyclic order. A gesture beats another if it comes an odd number of steps
+/// after it, going around the cycle, so every gesture beats exactly half of
+/// the others. With Rock, Paper, Scissors, Spock, Lizard this gives the
+/// familiar Rock-Paper-Scissors-Lizard-Spock rules.
+#[derive(Clone, Debug)]
+struct Rules {
+    names: Vec<&'static str>,
+    shape_points: Vec<i32>,
+    outcome_points: [i32; 3],
+}
+
+impl Rules {
+    /// Creates rules for the given gestures, scoring 1, 2, 3, ... points
+    /// for the gestures in order and 0, 3 and 6 points for losing, drawing
+    /// and winning. Panics if the number of gestures is even.
+    fn new(names: &[&'static str]) -> Self {
+        assert!(names.len() % 2 == 1, "need an odd number of gestures");
+
+        Rules {
+            names: names.to_vec(),
+            shape_points: (1..=names.len() as i32).collect(),
+            outcome_points: [0, 3, 6],
         }
     }
+
+    fn rock_paper_scissors() -> Self {
+        Self::new(&["Rock", "Paper", "Scissors"])
+    }
+
+    fn rock_paper_scissors_lizard_spock() -> Self {
+        Self::new(&["Rock", "Paper", "Scissors", "Spock", "Lizard"])
+    }
+
+    /// Sets the points for playing each gesture. Panics unless there is one
+    /// value per gesture.
+    fn with_shape_points(mut self, points: &[i32]) -> Self {
+        assert_eq!(points.len(), self.names.len(), "one value per gesture");
+        self.shape_points = points.to_vec();
+
+        self
+    }
+
+    /// Sets the points for losing, drawing and winning.
+    fn with_outcome_points(mut self, points: [i32; 3]) -> Self {
+        self.outcome_points = points;
+
+        self
+    }
+
+    /// Returns the outcome for the player of `mine` against `theirs`.
+    fn outcome(&self, mine: Gesture, theirs: Gesture) -> DesiredOutcome {
+        let n = self.names.len();
+
+        match (mine + n - theirs) % n {
+            0 => DesiredOutcome::Draw,
+            steps if steps % 2 == 1 => DesiredOutcome::Win,
+            _ => DesiredOutcome::Lose,
+        }
+    }
+
+    /// Returns the score for playing `mine` against `theirs`.
+    fn score(&self, mine: Gesture, theirs: Gesture) -> i32 {
+        self.shape_points[mine] + self.outcome_points[self.outcome(mine, theirs) as usize]
+    }
+
+    /// Returns the first gesture in order that gets `desired` against
+    /// `theirs`.
+    fn response_for(&self, theirs: Gesture, desired: DesiredOutcome) -> Gesture {
+        (0..self.names.len())
+            .find(|&mine| self.outcome(mine, theirs) == desired)
+            .expect("every outcome is possible")
+    }
 }
 
-fn score_part1(c: Call, r: Response) -> i32 {
-    match (c, r) {
-        (Call::Rock, Response::Rock) => 1 + 3,
-        (Call::Rock, Response::Paper) => 2 + 6,
-        (Call::Rock, Response::Scissors) => 3 + 0,
-        (Call::Paper, Response::Rock) => 1 + 0,
-        (Call::Paper, Response::Paper) => 2 + 3,
-        (Call::Paper, Response::Scissors) => 3 + 6,
-        (Call::Scissors, Response::Rock) => 1 + 6,
-        (Call::Scissors, Response::Paper) => 2 + 0,
-        (Call::Scissors, Response::Scissors) => 3 + 3,
+impl Call {
+    fn gesture(self) -> Gesture {
+        self as Gesture
     }
 }
 
-fn score_part2(c: Call, d: DesiredOutcome) -> Response {
-    match (c, d) {
-        (Call::Rock, DesiredOutcome::Lose) => Response::Scissors,
-        (Call::Rock, DesiredOutcome::Draw) => Response::Rock,
-        (Call::Rock, DesiredOutcome::Win) => Response::Paper,
-        (Call::Paper, DesiredOutcome::Lose) => Response::Rock,
-        (Call::Paper, DesiredOutcome::Draw) => Response::Paper,
-        (Call::Paper, DesiredOutcome::Win) => Response::Scissors,
-        (Call::Scissors, DesiredOutcome::Lose) => Response::Paper,
-        (Call::Scissors, DesiredOutcome::Draw) => Response::Scissors,
-        (Call::Scissors, DesiredOutcome::Win) => Response::Rock,
+impl Response {
+    fn gesture(self) -> Gesture {
+        self as Gesture
     }
 }
-fn main() {
-    let contents = std::fs::read_to_string("inputs/2.txt").expect("read input");
-
-    let score: i32 = contents
-        .split_terminator("\n")
-        .map(|game| {
-            let mut i = game.split_ascii_whitespace();
-            let c: Call = i.next().unwrap().into();
-            let r: Response = i.next().unwrap().into();
-            score_part1(c, r)
-        })
-        .sum();
-
-    let score: i32 = contents
-        .split_terminator("\n")
-        .map(|game| {
-            let mut i = game.split_ascii_whitespace();
-            let c: Call = i.next().unwrap().into();
-            let d: DesiredOutcome = i.next().unwrap().into();
-            score_part1(c, score_part2(c, d))
+
+/// Returns the gesture to play against `c` to get `d`. With more than three
+/// gestures this need not be one of the [Response] shapes.
+fn score_part2(rules: &Rules, c: Call, d: DesiredOutcome) -> Gesture {
+    rules.response_for(c.gesture(), d)
+}
+
+/// A way of reading the second column of the guide: either as the shape to
+/// play for X, Y and Z, or as the outcome to aim for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Interpretation {
+    Shapes([Response; 3]),
+    Outcomes,
+}
+
+impl Interpretation {
+    /// Returns all six shape mappings followed by the outcome reading.
+    fn all() -> Vec<Interpretation> {
+        let shapes = [Response::Rock, Response::Paper, Response::Scissors];
+        let mut all = vec![];
+
+        for x in shapes {
+            for y in shapes {
+                for z in shapes {
+                    if x != y && y != z && x != z {
+                        all.push(Interpretation::Shapes([x, y, z]));
+                    }
+                }
+            }
+        }
+        all.push(Interpretation::Outcomes);
+
+        all
+    }
+
+    /// Returns the gesture to play against `c` when the guide says `col`.
+    fn response(self, rules: &Rules, c: Call, col: Column) -> Gesture {
+        match self {
+            Interpretation::Shapes(shapes) => shapes[col as usize].gesture(),
+            Interpretation::Outcomes => {
+                let d = match col {
+                    Column::X => DesiredOutcome::Lose,
+                    Column::Y => DesiredOutcome::Draw,
+                    Column::Z => DesiredOutcome::Win,
+                };
+                score_part2(rules, c, d)
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Interpretation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Interpretation::Shapes([x, y, z]) => write!(f, "X={:?} Y={:?} Z={:?}", x, y, z),
+            Interpretation::Outcomes => write!(f, "X=Lose Y=Draw Z=Win"),
+        }
+    }
+}
+
+/// A single round played under some [Interpretation].
+#[derive(Debug)]
+struct Round {
+    call: Call,
+    column: Column,
+    response: Gesture,
+    outcome: DesiredOutcome,
+    score: i32,
+}
+
+/// The rounds and total score of the guide under one [Interpretation].
+#[derive(Debug)]
+struct Analysis {
+    interpretation: Interpretation,
+    rounds: Vec<Round>,
+    total: i32,
+}
+
+/// Plays the guide under one interpretation.
+fn play(rules: &Rules, guide: &[(Call, Column)], interpretation: Interpretation) -> Analysis {
+    let rounds: Vec<Round> = guide
+        .iter()
+        .map(|&(call, column)| {
+            let response = interpretation.response(rules, call, column);
+
+            Round {
+                call,
+                column,
+                response,
+                outcome: rules.outcome(response, call.gesture()),
+                score: rules.score(response, call.gesture()),
+            }
         })
-        .sum();
+        .collect();
+    let total = rounds.iter().map(|r| r.score).sum();
 
-    println!("score: {}", score)
+    Analysis {
+        interpretation,
+        rounds,
+        total,
+    }
+}
+
+/// Plays the guide under every interpretation, from the highest total score
+/// to the lowest. Ties keep the order of [Interpretation::all].
+fn analyze(rules: &Rules, guide: &[(Call, Column)]) -> Vec<Analysis> {
+    let mut analyses: Vec<Analysis> = Interpretation::all()
+        .into_iter()
+        .map(|interpretation| play(rules, guide, interpretation))
+        .collect();
+
+    analyses.sort_by_key(|a| std::cmp::Reverse(a.total));
+
+    analyses
+}
+
+fn print_breakdown(rules: &Rules, label: &str, analysis: &Analysis) {
+    println!(
+        "{}: {} ({})",
+        label, analysis.total, analysis.interpretation
+    );
+    for (i, r) in analysis.rounds.iter().enumerate() {
+        println!(
+            "  round {}: {:?} {:?} -> {}, {:?}, {}",
+            i + 1,
+            r.call,
+            r.column,
+            rules.names[r.response],
+            r.outcome,
+            r.score
+        );
+    }
+}
+
+/// Parses a comma separated list of points.
+fn parse_points(s: &str) -> Option<Vec<i32>> {
+    s.split(',').map(|p| p.parse().ok()).collect()
+}
+
+const USAGE: &str = "usage: 2 [--analyze] [--rules rps|rpsls] \
+                     [--shape-points p,...] [--outcome-points lose,draw,win]";
+
+/// Reads the command line: whether to analyze the guide, and the rules to
+/// play by.
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<(bool, Rules), String> {
+    let mut analyze_guide = false;
+    let mut rules = Rules::rock_paper_scissors();
+    let mut shape_points = None;
+    let mut outcome_points = None;
+
+    while let Some(arg) = args.next() {
+        if arg == "--analyze" {
+            analyze_guide = true;
+        } else if arg == "--rules" {
+            rules = match args.next().as_deref() {
+                Some("rps") => Rules::rock_paper_scissors(),
+                Some("rpsls") => Rules::rock_paper_scissors_lizard_spock(),
+                _ => return Err("--rules needs rps or rpsls".to_string()),
+            };
+        } else if arg == "--shape-points" {
+            shape_points = Some(
+                args.next()
+                    .as_deref()
+                    .and_then(parse_points)
+                    .ok_or("--shape-points needs a list of numbers")?,
+            );
+        } else if arg == "--outcome-points" {
+            outcome_points = Some(
+                args.next()
+                    .as_deref()
+                    .and_then(parse_points)
+                    .and_then(|p| <[i32; 3]>::try_from(p).ok())
+                    .ok_or("--outcome-points needs three numbers: lose, draw and win")?,
+            );
+        } else {
+            return Err(format!("unknown argument {:?}", arg));
+        }
+    }
+
+    if let Some(points) = shape_points {
+        if points.len() != rules.names.len() {
+            return Err(format!(
+                "--shape-points needs {} numbers, one for each of {}",
+                rules.names.len(),
+                rules.names.join(", ")
+            ));
+        }
+        rules = rules.with_shape_points(&points);
+    }
+    if let Some(points) = outcome_points {
+        rules = rules.with_outcome_points(points);
+    }
+
+    Ok((analyze_guide, rules))
+}
+
+fn main() {
+    let (analyze_guide, rules) = parse_args(std::env::args().skip(1)).unwrap_or_else(|e| {
+        eprintln!("error: {}\n{}", e, USAGE);
+        std::process::exit(2);
+    });
+
+    aoc2022::run(2, |contents| {
+        let guide: Vec<(Call, Column)> = parse_lines(contents, parse_game)?;
+
+        let shapes = Interpretation::Shapes([Response::Rock, Response::Paper, Response::Scissors]);
+        let _score = play(&rules, &guide, shapes).total;
+        println!(
+            "score: {}",
+            play(&rules, &guide, Interpretation::Outcomes).total
+        );
+
+        if analyze_guide {
+            let analyses = analyze(&rules, &guide);
+
+            for a in &analyses {
+                println!("{}: {}", a.interpretation, a.total);
+            }
+            print_breakdown(&rules, "highest", &analyses[0]);
+            print_breakdown(&rules, "lowest", &analyses[analyses.len() - 1]);
+        }
+
+        Ok(())
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GUIDE: &str = "A Y\nB X\nC Z";
+
+    #[test]
+    fn existing_scores() {
+        let rules = Rules::rock_paper_scissors();
+        let guide = parse_lines(GUIDE, parse_game).unwrap();
+        let shapes = Interpretation::Shapes([Response::Rock, Response::Paper, Response::Scissors]);
+
+        assert_eq!(play(&rules, &guide, shapes).total, 15);
+        assert_eq!(play(&rules, &guide, Interpretation::Outcomes).total, 12);
+    }
+
+    #[test]
+    fn lizard_spock() {
+        let rules = Rules::rock_paper_scissors_lizard_spock();
+        let g = |name| rules.names.iter().position(|n| *n == name).unwrap();
+
+        for (winner, loser) in [
+            ("Scissors", "Paper"),
+            ("Paper", "Rock"),
+            ("Rock", "Lizard"),
+            ("Lizard", "Spock"),
+            ("Spock", "Scissors"),
+            ("Scissors", "Lizard"),
+            ("Lizard", "Paper"),
+            ("Paper", "Spock"),
+            ("Spock", "Rock"),
+            ("Rock", "Scissors"),
+        ] {
+            assert_eq!(rules.outcome(g(winner), g(loser)), DesiredOutcome::Win);
+            assert_eq!(rules.outcome(g(loser), g(winner)), DesiredOutcome::Lose);
+        }
+
+        assert_eq!(
+            rules.response_for(g("Spock"), DesiredOutcome::Win),
+            g("Paper")
+        );
+    }
+
+    #[test]
+    fn balanced_and_configurable() {
+        let rules = Rules::new(&["A", "B", "C", "D", "E", "F", "G"])
+            .with_shape_points(&[0; 7])
+            .with_outcome_points([-1, 0, 1]);
+
+        for mine in 0..7 {
+            let wins = (0..7)
+                .filter(|&theirs| rules.outcome(mine, theirs) == DesiredOutcome::Win)
+                .count();
+            let total: i32 = (0..7).map(|theirs| rules.score(mine, theirs)).sum();
+
+            assert_eq!(wins, 3);
+            assert_eq!(total, 0);
+        }
+    }
+
+    #[test]
+    fn analysis() {
+        let rules = Rules::rock_paper_scissors();
+        let guide = parse_lines(GUIDE, parse_game).unwrap();
+        let analyses = analyze(&rules, &guide);
+
+        assert_eq!(analyses.len(), 7);
+
+        let total = |i: Interpretation| {
+            analyses
+                .iter()
+                .find(|a| a.interpretation == i)
+                .unwrap()
+                .total
+        };
+        use Response::*;
+
+        assert_eq!(total(Interpretation::Shapes([Rock, Paper, Scissors])), 15);
+        assert_eq!(total(Interpretation::Outcomes), 12);
+
+        // A Y, B X, C Z: playing Paper, Scissors and Rock wins every round.
+        assert_eq!(
+            analyses[0].interpretation,
+            Interpretation::Shapes([Scissors, Paper, Rock])
+        );
+        assert_eq!(analyses[0].total, 24);
+        assert_eq!(
+            analyses[0]
+                .rounds
+                .iter()
+                .map(|r| (r.response, r.outcome, r.score))
+                .collect::<Vec<_>>(),
+            vec![
+                (Paper.gesture(), DesiredOutcome::Win, 8),
+                (Scissors.gesture(), DesiredOutcome::Win, 9),
+                (Rock.gesture(), DesiredOutcome::Win, 7)
+            ]
+        );
+        assert!(analyses.windows(2).all(|w| w[0].total >= w[1].total));
+    }
+
+    #[test]
+    fn arguments() {
+        let args = |a: &[&str]| parse_args(a.iter().map(|s| s.to_string()));
+
+        let (analyze_guide, rules) = args(&["--rules", "rpsls", "--analyze"]).unwrap();
+        assert!(analyze_guide);
+        assert_eq!(rules.names.len(), 5);
+
+        assert_eq!(
+            args(&["--rules", "rpsls", "--shape-points", "1,2,3"]).unwrap_err(),
+            "--shape-points needs 5 numbers, one for each of Rock, Paper, Scissors, Spock, Lizard"
+        );
+        assert_eq!(
+            args(&["--outcome-points", "0,3"]).unwrap_err(),
+            "--outcome-points needs three numbers: lose, draw and win"
+        );
+        assert_eq!(
+            args(&["--anlyze"]).unwrap_err(),
+            "unknown argument \"--anlyze\""
+        );
+    }
 }
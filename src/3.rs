@@ -1,66 +1,391 @@
-use std::collections::HashSet;
+use aoc2022::parse::ParseError;
 
-fn priority(c: u8) -> u32 {
+fn priority(c: u8) -> Option<u32> {
     let p = match c {
         b'a'..=b'z' => c - 0x60,
         b'A'..=b'Z' => c - 0x40 + 26,
-        _ => panic!("lol char"),
+        _ => return None,
     };
 
-    p as u32
+    Some(p as u32)
+}
+
+/// A set of items, stored as one bit per priority.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+struct ItemSet(u64);
+
+impl ItemSet {
+    /// Returns the set containing every possible item.
+    fn all() -> Self {
+        ItemSet((1..=52).fold(0, |bits, p| bits | 1 << p))
+    }
+
+    fn insert(&mut self, priority: u32) {
+        self.0 |= 1 << priority;
+    }
+
+    fn intersection(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 & other.0)
+    }
+
+    fn union(self, other: ItemSet) -> ItemSet {
+        ItemSet(self.0 | other.0)
+    }
+
+    fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// Returns an iterator over the priorities in the set, lowest first.
+    fn iter(self) -> Items {
+        Items(self.0)
+    }
+}
+
+impl FromIterator<u32> for ItemSet {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> Self {
+        let mut set = ItemSet::default();
+        for p in iter {
+            set.insert(p);
+        }
+
+        set
+    }
+}
+
+/// An iterator over the priorities in an [ItemSet].
+struct Items(u64);
+
+impl Iterator for Items {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.0 == 0 {
+            return None;
+        }
+
+        let p = self.0.trailing_zeros();
+        self.0 &= self.0 - 1;
+
+        Some(p)
+    }
+}
+
+/// Parses a rucksack into the items of its two compartments.
+fn parse_rucksack(line: &str) -> Result<(ItemSet, ItemSet), ParseError> {
+    if !line.len().is_multiple_of(2) {
+        return Err(ParseError::new(1, "odd number of items"));
+    }
+
+    let mut compartments = [ItemSet::default(); 2];
+
+    for (i, c) in line.bytes().enumerate() {
+        let p = priority(c)
+            .ok_or_else(|| ParseError::new(i + 1, format!("unknown item {:?}", c as char)))?;
+        compartments[i / (line.len() / 2)].insert(p);
+    }
+
+    Ok((compartments[0], compartments[1]))
+}
+
+/// Returns the single item in `common`, or an error on the line
+/// `line_offset` lines down.
+fn only_common(common: ItemSet, line_offset: usize) -> Result<u32, ParseError> {
+    let mut items = common.iter();
+
+    match (items.next(), common.len()) {
+        (Some(item), 1) => Ok(item),
+        _ => Err(ParseError::new(
+            1,
+            format!("found {} common items, expected 1", common.len()),
+        )
+        .offset_lines(line_offset)),
+    }
+}
+
+/// A problem found by [validate].
+#[derive(Debug, PartialEq, Eq)]
+enum Problem {
+    UnknownItem {
+        line_no: usize,
+        column: usize,
+        c: char,
+    },
+    OddLength {
+        line_no: usize,
+    },
+    Compartments {
+        line_no: usize,
+        common: usize,
+    },
+    Group {
+        first_line: usize,
+        last_line: usize,
+        common: usize,
+    },
+    IncompleteGroup {
+        first_line: usize,
+        len: usize,
+    },
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Problem::UnknownItem { line_no, column, c } => {
+                write!(
+                    f,
+                    "line {}, column {}: unknown item {:?}",
+                    line_no, column, c
+                )
+            }
+            Problem::OddLength { line_no } => write!(f, "line {}: odd number of items", line_no),
+            Problem::Compartments { line_no, common } => write!(
+                f,
+                "line {}: compartments share {} items, expected 1",
+                line_no, common
+            ),
+            Problem::Group {
+                first_line,
+                last_line,
+                common,
+            } => write!(
+                f,
+                "lines {}-{}: group shares {} items, expected 1",
+                first_line, last_line, common
+            ),
+            Problem::IncompleteGroup { first_line, len } => write!(
+                f,
+                "line {}: incomplete group of {} rucksacks",
+                first_line, len
+            ),
+        }
+    }
+}
+
+/// Checks every rucksack and every group of `group_size` rucksacks,
+/// returning all problems instead of stopping at the first. Unknown items
+/// are left out of the other checks. Panics if `group_size` is less than 2,
+/// since a badge is what several elves have in common.
+fn validate(contents: &str, group_size: usize) -> Vec<Problem> {
+    assert!(group_size >= 2, "groups need at least two rucksacks");
+
+    let mut problems = vec![];
+    let mut badge = ItemSet::all();
+    let mut rucksacks = 0;
+
+    for (i, line) in contents.lines().enumerate() {
+        let line_no = i + 1;
+        let mut compartments = [ItemSet::default(); 2];
+
+        for (j, c) in line.bytes().enumerate() {
+            match priority(c) {
+                Some(p) => compartments[(2 * j / line.len().max(1)).min(1)].insert(p),
+                None => problems.push(Problem::UnknownItem {
+                    line_no,
+                    column: j + 1,
+                    c: c as char,
+                }),
+            }
+        }
+
+        let [left, right] = compartments;
+        if !line.len().is_multiple_of(2) {
+            problems.push(Problem::OddLength { line_no });
+        } else if left.intersection(right).len() != 1 {
+            problems.push(Problem::Compartments {
+                line_no,
+                common: left.intersection(right).len(),
+            });
+        }
+
+        badge = badge.intersection(left.union(right));
+        if line_no % group_size == 0 {
+            if badge.len() != 1 {
+                problems.push(Problem::Group {
+                    first_line: line_no + 1 - group_size,
+                    last_line: line_no,
+                    common: badge.len(),
+                });
+            }
+            badge = ItemSet::all();
+        }
+
+        rucksacks += 1;
+    }
+
+    if rucksacks % group_size != 0 {
+        problems.push(Problem::IncompleteGroup {
+            first_line: rucksacks - rucksacks % group_size + 1,
+            len: rucksacks % group_size,
+        });
+    }
+
+    problems
 }
 
 fn main() {
-    let contents = std::fs::read_to_string("inputs/3.txt").expect("read input");
+    let mut group_size = 3;
+    let mut validate_only = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--group-size" {
+            group_size = args
+                .next()
+                .and_then(|n| n.parse().ok())
+                .filter(|&n| n >= 2)
+                .expect("--group-size needs a number of at least 2");
+        } else if arg == "--validate" {
+            validate_only = true;
+        }
+    }
+
+    aoc2022::run(3, |contents| {
+        if validate_only {
+            let problems = validate(contents, group_size);
 
-    let total_priority: u32 = contents
-        .lines()
-        .map(|l| {
-            let (left, right) = l.split_at(l.len() / 2);
+            for problem in &problems {
+                println!("{}", problem);
+            }
+            println!("problems: {}", problems.len());
+
+            return Ok(());
+        }
 
-            dbg!(&left);
-            dbg!(&right);
+        let mut _total_priority = 0;
+        let mut badge_priority = 0;
+        let mut badge = ItemSet::all();
+        let mut rucksacks: usize = 0;
 
-            let left = HashSet::<u8>::from_iter(left.bytes());
-            let right = HashSet::<u8>::from_iter(right.bytes());
+        for (i, line) in contents.lines().enumerate() {
+            let (left, right) = parse_rucksack(line).map_err(|e| e.offset_lines(i))?;
 
-            let only: Vec<u8> = left.intersection(&right).cloned().collect();
+            _total_priority += only_common(left.intersection(right), i)?;
 
-            if only.len() != 1 {
-                dbg!("wtf");
+            badge = badge.intersection(left.union(right));
+            if (i + 1) % group_size == 0 {
+                badge_priority += only_common(badge, i + 1 - group_size)?;
+                badge = ItemSet::all();
             }
 
-            let only = only.first().cloned().unwrap();
+            rucksacks += 1;
+        }
+
+        if !rucksacks.is_multiple_of(group_size) {
+            return Err(ParseError::new(
+                1,
+                format!("incomplete group of {} rucksacks", rucksacks % group_size),
+            )
+            .offset_lines(rucksacks - rucksacks % group_size));
+        }
+
+        println!("pri: {}", badge_priority);
+
+        Ok(())
+    });
+}
 
-            dbg!(&only);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            priority(only)
-        })
-        .sum();
+    #[test]
+    fn item_set() {
+        let (left, right) = parse_rucksack("vJrwpWtwJgWrhcsFMMfFFhFp").unwrap();
 
-    let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(left.intersection(right).iter().collect::<Vec<_>>(), [16]);
+        assert_eq!(left.union(right).len(), 14);
+        assert_eq!(ItemSet::all().len(), 52);
+        assert_eq!(
+            [3, 1, 52]
+                .into_iter()
+                .collect::<ItemSet>()
+                .iter()
+                .collect::<Vec<_>>(),
+            [1, 3, 52]
+        );
+    }
 
-    let total_priority: u32 = lines
-        .chunks(3)
-        .map(|w| {
-            let one = w[0].bytes().collect::<HashSet<_>>();
-            let two = w[1].bytes().collect::<HashSet<_>>();
-            let three = w[2].bytes().collect::<HashSet<_>>();
+    #[test]
+    fn not_one_common() {
+        let e = only_common(ItemSet::default(), 4).unwrap_err();
 
-            dbg!(&one);
-            dbg!(&two);
-            dbg!(&three);
+        assert_eq!(e.line_no, 5);
+        assert_eq!(e.reason, "found 0 common items, expected 1");
+    }
 
-            let kek = one.intersection(&two).cloned().collect::<HashSet<_>>();
-            let only = kek.intersection(&three).collect::<Vec<_>>();
+    #[test]
+    fn validation_report() {
+        let contents = "vJrwpWtwJgWrhcsFMMfFFhFp\n\
+                        jqHRNqRjqzjGDLGLrsFMfFZSrLrFZsSL\n\
+                        PmmdzqPrVvPwwTWBwg\n\
+                        abcabc\n\
+                        aB1xyz\n\
+                        Bbc\n\
+                        ab";
 
-            let only = only.first().cloned().unwrap();
+        assert_eq!(
+            validate(contents, 3),
+            [
+                Problem::Compartments {
+                    line_no: 4,
+                    common: 3
+                },
+                Problem::UnknownItem {
+                    line_no: 5,
+                    column: 3,
+                    c: '1'
+                },
+                Problem::Compartments {
+                    line_no: 5,
+                    common: 0
+                },
+                Problem::OddLength { line_no: 6 },
+                Problem::Group {
+                    first_line: 4,
+                    last_line: 6,
+                    common: 0
+                },
+                Problem::Compartments {
+                    line_no: 7,
+                    common: 0
+                },
+                Problem::IncompleteGroup {
+                    first_line: 7,
+                    len: 1
+                },
+            ]
+        );
+    }
 
-            dbg!(&only);
+    #[test]
+    fn validation_report_pairs() {
+        let contents = "abcb\nxbyb\nAcAd\ndBdB\nzz\nyy\nqq";
 
-            priority(*only)
-        })
-        .sum();
+        assert_eq!(
+            validate(contents, 2),
+            [
+                Problem::Compartments {
+                    line_no: 4,
+                    common: 2
+                },
+                Problem::Group {
+                    first_line: 5,
+                    last_line: 6,
+                    common: 0
+                },
+                Problem::IncompleteGroup {
+                    first_line: 7,
+                    len: 1
+                },
+            ]
+        );
+    }
 
-    println!("pri: {}", total_priority);
+    #[test]
+    #[should_panic(expected = "groups need at least two rucksacks")]
+    fn validation_single_rucksack_groups() {
+        validate("abcb", 1);
+    }
 }
@@ -1,40 +1,330 @@
-use std::collections::HashSet;
+use std::ops::RangeInclusive;
 
-fn parse_range(s: &str) -> Option<HashSet<i32>> {
-    let (from, to) = s.split_once('-')?;
-    let (from, to) = (from.parse::<i32>().ok()?, to.parse::<i32>().ok()?);
+use aoc2022::parse::{parse_lines, ParseError};
 
-    Some((from..=to).collect())
+/// An inclusive range of section ids.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Interval(RangeInclusive<i64>);
+
+impl Interval {
+    fn new(start: i64, end: i64) -> Self {
+        Interval(start..=end)
+    }
+
+    fn start(&self) -> i64 {
+        *self.0.start()
+    }
+
+    fn end(&self) -> i64 {
+        *self.0.end()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the number of sections in the interval.
+    fn len(&self) -> i128 {
+        if self.is_empty() {
+            0
+        } else {
+            self.end() as i128 - self.start() as i128 + 1
+        }
+    }
+
+    /// Returns true if every section of `other` is also in `self`.
+    fn contains(&self, other: &Interval) -> bool {
+        other.is_empty() || (self.start() <= other.start() && other.end() <= self.end())
+    }
+
+    /// Returns true if `self` and `other` have a section in common.
+    fn overlaps(&self, other: &Interval) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// Returns the sections in both `self` and `other`, or `None` if there
+    /// are none.
+    fn intersection(&self, other: &Interval) -> Option<Interval> {
+        let i = Interval::new(self.start().max(other.start()), self.end().min(other.end()));
+
+        (!i.is_empty()).then_some(i)
+    }
 }
 
-fn main() {
-    let contents = std::fs::read_to_string("inputs/4.txt").expect("read input");
+/// A set of sections, stored as sorted intervals that neither overlap nor
+/// touch.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+struct IntervalSet(Vec<Interval>);
 
-    let ranges: Vec<(HashSet<i32>, HashSet<i32>)> = contents
-        .lines()
-        .map(|l| {
-            let (left, right) = l.split_once(',').expect("split once at comma");
+impl IntervalSet {
+    /// Adds the sections in `interval`, merging it with every interval it
+    /// overlaps or touches.
+    fn insert(&mut self, interval: Interval) {
+        if interval.is_empty() {
+            return;
+        }
 
-            let left = parse_range(left).expect("found range");
-            let right = parse_range(right).expect("found range");
+        let (mut start, mut end) = (interval.start(), interval.end());
+        let lo = self
+            .0
+            .partition_point(|i| i.end() < start.saturating_sub(1));
+        let hi = self
+            .0
+            .partition_point(|i| i.start() <= end.saturating_add(1));
 
-            (left, right)
-        })
-        .collect();
+        if lo < hi {
+            start = start.min(self.0[lo].start());
+            end = end.max(self.0[hi - 1].end());
+        }
 
-    println!(
-        "contained: {}",
-        ranges
-            .iter()
-            .filter(|(l, r)| l.is_subset(r) || l.is_superset(r))
-            .count()
-    );
+        self.0.splice(lo..hi, [Interval::new(start, end)]);
+    }
+
+    /// Returns an iterator over the intervals, lowest first.
+    fn iter(&self) -> std::slice::Iter<'_, Interval> {
+        self.0.iter()
+    }
+
+    /// Returns the number of sections in the set.
+    fn sections(&self) -> i128 {
+        self.iter().map(Interval::len).sum()
+    }
+}
+
+impl FromIterator<Interval> for IntervalSet {
+    fn from_iter<I: IntoIterator<Item = Interval>>(iter: I) -> Self {
+        let mut set = IntervalSet::default();
+        for interval in iter {
+            set.insert(interval);
+        }
+
+        set
+    }
+}
 
-    println!(
-        "overlaps: {}",
-        ranges
+impl std::fmt::Display for Interval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}-{}", self.start(), self.end())
+    }
+}
+
+impl std::fmt::Display for IntervalSet {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.0.is_empty() {
+            return write!(f, "none");
+        }
+
+        for (i, interval) in self.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{}", interval)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// The number of elves assigned to each section between the lowest and the
+/// highest assigned section, as consecutive intervals of equal depth.
+#[derive(Debug)]
+struct Coverage(Vec<(Interval, usize)>);
+
+impl Coverage {
+    /// Sweeps over the start and end of every assignment.
+    fn new<'a>(assignments: impl IntoIterator<Item = &'a Interval>) -> Self {
+        // Wide enough for the section after i64::MAX.
+        let mut events: Vec<(i128, isize)> = assignments
+            .into_iter()
+            .filter(|a| !a.is_empty())
+            .flat_map(|a| [(a.start() as i128, 1), (a.end() as i128 + 1, -1)])
+            .collect();
+        events.sort_unstable();
+
+        let mut segments = vec![];
+        let mut depth = 0;
+
+        for (i, &(pos, delta)) in events.iter().enumerate() {
+            depth += delta;
+
+            match events.get(i + 1) {
+                Some(&(next, _)) if next > pos => {
+                    // Both fit in an i64, since a segment ends where an
+                    // assignment does.
+                    segments.push((Interval::new(pos as i64, (next - 1) as i64), depth as usize))
+                }
+                _ => (),
+            }
+        }
+
+        Coverage(segments)
+    }
+
+    fn max_depth(&self) -> usize {
+        self.0.iter().map(|(_, depth)| *depth).max().unwrap_or(0)
+    }
+
+    /// Returns the sections assigned to exactly `depth` elves.
+    fn at_depth(&self, depth: usize) -> IntervalSet {
+        self.0
             .iter()
-            .filter(|(l, r)| l.intersection(r).count() > 0)
-            .count()
-    );
+            .filter(|(_, d)| *d == depth)
+            .map(|(interval, _)| interval.clone())
+            .collect()
+    }
+
+    /// Returns the sections assigned to the most elves.
+    fn deepest(&self) -> IntervalSet {
+        self.at_depth(self.max_depth())
+    }
+
+    /// Returns the unassigned sections between assigned ones.
+    fn gaps(&self) -> IntervalSet {
+        self.at_depth(0)
+    }
+}
+
+fn parse_range(s: &str) -> Result<Interval, ParseError> {
+    let (from, to) = s
+        .split_once('-')
+        .ok_or_else(|| ParseError::new(1, format!("expected a range, found {:?}", s)))?;
+
+    let parse = |n: &str, column: usize| {
+        n.parse::<i64>()
+            .map_err(|e| ParseError::new(column, format!("cannot parse {:?}: {}", n, e)))
+    };
+    let (start, end) = (parse(from, 1)?, parse(to, from.len() + 2)?);
+
+    if end < start {
+        return Err(ParseError::new(
+            from.len() + 2,
+            format!("range {:?} ends before it starts", s),
+        ));
+    }
+
+    Ok(Interval::new(start, end))
+}
+
+fn parse_pair(l: &str) -> Result<(Interval, Interval), ParseError> {
+    let (left, right) = l
+        .split_once(',')
+        .ok_or_else(|| ParseError::new(1, "expected two ranges separated by ','"))?;
+
+    let left = parse_range(left)?;
+    let right = parse_range(right).map_err(|e| e.offset_columns(l.len() - right.len()))?;
+
+    Ok((left, right))
+}
+
+fn main() {
+    let show_coverage = std::env::args().skip(1).any(|arg| arg == "--coverage");
+
+    aoc2022::run(4, |contents| {
+        let ranges = parse_lines(contents, parse_pair)?;
+
+        println!(
+            "contained: {}",
+            ranges
+                .iter()
+                .filter(|(l, r)| l.contains(r) || r.contains(l))
+                .count()
+        );
+
+        println!(
+            "overlaps: {}",
+            ranges.iter().filter(|(l, r)| l.overlaps(r)).count()
+        );
+
+        if show_coverage {
+            let covered: IntervalSet = ranges
+                .iter()
+                .flat_map(|(l, r)| [l.clone(), r.clone()])
+                .collect();
+
+            println!("covered: {}", covered.sections());
+
+            let coverage = Coverage::new(ranges.iter().flat_map(|(l, r)| [l, r]));
+
+            println!("max depth: {}", coverage.max_depth());
+            println!("most elves: {}", coverage.deepest());
+            println!("gaps: {}", coverage.gaps());
+        }
+
+        Ok(())
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval() {
+        let a = Interval::new(2, 8);
+        let b = Interval::new(3, 7);
+        let c = Interval::new(8, 1_000_000_000);
+
+        assert!(a.contains(&b));
+        assert!(!b.contains(&a));
+        assert!(a.overlaps(&c));
+        assert!(!b.overlaps(&c));
+        assert_eq!(a.intersection(&c), Some(Interval::new(8, 8)));
+        assert_eq!(b.intersection(&c), None);
+        assert_eq!(c.len(), 999_999_993);
+    }
+
+    #[test]
+    fn interval_set() {
+        let set: IntervalSet = [(10, 12), (1, 2), (5, 6), (3, 3), (7, 11), (20, 19)]
+            .into_iter()
+            .map(|(s, e)| Interval::new(s, e))
+            .collect();
+
+        assert_eq!(
+            set.iter().collect::<Vec<_>>(),
+            [&Interval::new(1, 3), &Interval::new(5, 12)]
+        );
+        assert_eq!(set.sections(), 11);
+    }
+
+    #[test]
+    fn coverage() {
+        let assignments: Vec<Interval> = [(2, 4), (6, 8), (2, 3), (4, 5), (7, 8), (20, 20)]
+            .into_iter()
+            .map(|(s, e)| Interval::new(s, e))
+            .collect();
+        let coverage = Coverage::new(&assignments);
+
+        assert_eq!(coverage.max_depth(), 2);
+        assert_eq!(coverage.deepest().to_string(), "2-4, 7-8");
+        assert_eq!(coverage.gaps().to_string(), "9-19");
+        assert_eq!(coverage.at_depth(1).to_string(), "5-6, 20-20");
+        assert_eq!(Coverage::new(&[]).deepest().to_string(), "none");
+
+        let whole = [
+            Interval::new(0, i64::MAX),
+            Interval::new(i64::MAX, i64::MAX),
+        ];
+        let coverage = Coverage::new(&whole);
+
+        assert_eq!(
+            coverage.deepest().to_string(),
+            "9223372036854775807-9223372036854775807"
+        );
+        assert_eq!(coverage.at_depth(1).sections(), i64::MAX as i128);
+        assert_eq!(whole[0].len(), i64::MAX as i128 + 1);
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            parse_pair("2-4,6-1000000000").unwrap(),
+            (Interval::new(2, 4), Interval::new(6, 1_000_000_000))
+        );
+
+        let e = parse_pair("2-4,6-5").unwrap_err();
+
+        assert_eq!(e.column, 7);
+        assert_eq!(e.reason, "range \"6-5\" ends before it starts");
+    }
 }
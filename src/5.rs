@@ -1,4 +1,23 @@
-#[derive(Clone, Debug)]
+use std::thread;
+use std::time::Duration;
+
+use aoc2022::parse::{blocks, parse_lines, FromLine, ParseError};
+
+#[derive(Clone, Copy, Debug, FromLine)]
+#[pattern("move {n} from {from} to {to}")]
+struct Move {
+    n: usize,
+    from: usize,
+    to: usize,
+}
+
+impl std::fmt::Display for Move {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "move {} from {} to {}", self.n, self.from, self.to)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Stack(Vec<char>);
 
 impl Stack {
@@ -6,10 +25,6 @@ impl Stack {
         Self(vec![])
     }
 
-    fn pop(&mut self) -> Option<char> {
-        self.0.pop()
-    }
-
     fn push(&mut self, c: char) {
         self.0.push(c)
     }
@@ -23,45 +38,248 @@ impl Stack {
     }
 
     fn push_many(&mut self, v: Vec<char>) {
-        self.0.extend(v.into_iter());
+        self.0.extend(v);
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 struct Stacks(Vec<Stack>);
 
-impl Stacks {
-    fn from(diagram: &str) -> Self {
+impl TryFrom<&str> for Stacks {
+    type Error = ParseError;
+
+    fn try_from(diagram: &str) -> Result<Self, ParseError> {
         let mut riter = diagram.lines().rev();
+        let numbers_line_no = diagram.lines().count().saturating_sub(1);
 
-        let numbers_line = riter.next().unwrap();
+        let numbers_line = riter
+            .next()
+            .ok_or_else(|| ParseError::new(1, "missing stack diagram"))?;
 
-        let indices: Vec<usize> = numbers_line
-            .match_indices(|c: char| c.is_ascii_digit())
-            .map(|(idx, _s)| idx)
-            .collect();
+        // Stack i is labelled i + 1, and its crates are drawn in column
+        // 4 * i + 1, however many digits the label has.
+        let mut indices = vec![];
+        let mut start = 0;
+
+        for label in numbers_line.split(' ') {
+            if !label.is_empty() {
+                let expected = indices.len() + 1;
+                if label.parse() != Ok(expected) {
+                    return Err(ParseError::new(
+                        start + 1,
+                        format!("expected stack number {}, found {:?}", expected, label),
+                    )
+                    .offset_lines(numbers_line_no));
+                }
+                indices.push(4 * indices.len() + 1);
+            }
+            start += label.len() + 1;
+        }
+
+        if indices.is_empty() {
+            return Err(ParseError::new(1, "expected stack numbers").offset_lines(numbers_line_no));
+        }
 
         let mut stacks = vec![Stack::new(); indices.len()];
 
-        for line in riter {
+        for (line_no, line) in riter.enumerate() {
             for (stack_idx, char_idx) in indices.iter().enumerate() {
-                if let Some(c) = line.chars().nth(*char_idx) {
-                    if c.is_uppercase() {
-                        stacks[stack_idx].push(c)
+                match line.chars().nth(*char_idx) {
+                    Some(c) if c.is_uppercase() => stacks[stack_idx].push(c),
+                    Some(' ') | None => (),
+                    Some(c) => {
+                        return Err(ParseError::new(
+                            char_idx + 1,
+                            format!("unexpected crate {:?}", c),
+                        )
+                        .offset_lines(numbers_line_no - line_no - 1))
                     }
                 }
             }
         }
 
-        Self(stacks)
+        Ok(Self(stacks))
+    }
+}
+
+/// Draws the stacks the way the puzzle input does, ending with the line of
+/// stack numbers.
+impl std::fmt::Display for Stacks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let height = self.0.iter().map(|s| s.0.len()).max().unwrap_or(0);
+
+        for level in (0..height).rev() {
+            for (i, stack) in self.0.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " ")?;
+                }
+                match stack.0.get(level) {
+                    Some(c) => write!(f, "[{}]", c)?,
+                    None => write!(f, "   ")?,
+                }
+            }
+            writeln!(f)?;
+        }
+
+        for i in 0..self.0.len() {
+            if i > 0 {
+                write!(f, " ")?;
+            }
+            write!(f, "{:^3}", i + 1)?;
+        }
+
+        writeln!(f)
+    }
+}
+
+/// Writes `stacks` and `moves` as puzzle input, which parses back to the
+/// same stacks and moves.
+fn write_input(stacks: &Stacks, moves: &[Move]) -> String {
+    let mut s = format!("{}\n", stacks);
+    for m in moves {
+        s.push_str(&format!("{}\n", m));
+    }
+
+    s
+}
+
+/// Parses the stack diagram and the moves, along with the number of lines
+/// before the first move. Input without moves gives an empty list.
+fn parse_input(contents: &str) -> Result<(Stacks, Vec<Move>, usize), ParseError> {
+    let mut iter = blocks(contents);
+
+    let (_, diagram) = iter
+        .next()
+        .ok_or_else(|| ParseError::new(1, "missing stack diagram"))?;
+    let stacks = Stacks::try_from(diagram)?;
+
+    let (offset, instructions) = iter.next().unwrap_or((0, ""));
+    let moves = parse_lines(instructions, Move::from_line).map_err(|e| e.offset_lines(offset))?;
+
+    Ok((stacks, moves, offset))
+}
+
+/// A crane model, deciding how many crates each lift of a move carries.
+/// Displays as the name used on the command line.
+trait Crane: std::fmt::Debug + std::fmt::Display {
+    /// Returns the number of crates in each lift used to move `n` crates, in
+    /// order. A lift keeps the order of the crates it carries.
+    fn lifts(&self, n: usize) -> Vec<usize>;
+}
+
+/// Moves one crate at a time.
+#[derive(Debug)]
+struct CrateMover9000;
+
+/// Moves all the crates of a move at once.
+#[derive(Debug)]
+struct CrateMover9001;
+
+/// Moves at most this many crates at once.
+#[derive(Debug)]
+struct LimitedCrane(usize);
+
+impl std::fmt::Display for CrateMover9000 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "9000")
+    }
+}
+
+impl std::fmt::Display for CrateMover9001 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "9001")
+    }
+}
+
+impl std::fmt::Display for LimitedCrane {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "limited:{}", self.0)
+    }
+}
+
+impl Crane for CrateMover9000 {
+    fn lifts(&self, n: usize) -> Vec<usize> {
+        vec![1; n]
+    }
+}
+
+impl Crane for CrateMover9001 {
+    fn lifts(&self, n: usize) -> Vec<usize> {
+        vec![n]
+    }
+}
+
+impl Crane for LimitedCrane {
+    fn lifts(&self, n: usize) -> Vec<usize> {
+        let mut lifts = vec![self.0; n / self.0];
+        if !n.is_multiple_of(self.0) {
+            lifts.push(n % self.0);
+        }
+
+        lifts
+    }
+}
+
+/// Parses a crane model as given on the command line: `9000`, `9001` or
+/// `limited:k`.
+fn parse_crane(s: &str) -> Option<Box<dyn Crane>> {
+    match s {
+        "9000" => Some(Box::new(CrateMover9000)),
+        "9001" => Some(Box::new(CrateMover9001)),
+        _ => {
+            let k = s.strip_prefix("limited:")?.parse().ok()?;
+            (k > 0).then(|| Box::new(LimitedCrane(k)) as Box<dyn Crane>)
+        }
+    }
+}
+
+impl Stacks {
+    /// Checks that `m` names existing stacks and that there are enough
+    /// crates to move.
+    fn check(&self, m: &Move) -> Result<(), ParseError> {
+        for stack in [m.from, m.to] {
+            if stack == 0 || stack > self.0.len() {
+                return Err(ParseError::new(
+                    1,
+                    format!("no stack {}, there are {}", stack, self.0.len()),
+                ));
+            }
+        }
+
+        let crates = self.0[m.from - 1].0.len();
+        if crates < m.n {
+            return Err(ParseError::new(
+                1,
+                format!(
+                    "cannot move {} crates from stack {} holding {}",
+                    m.n, m.from, crates
+                ),
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Applies `m` with the given crane, or returns an error and leaves the
+    /// stacks untouched if the move is impossible.
+    fn apply(&mut self, crane: &dyn Crane, m: &Move) -> Result<(), ParseError> {
+        self.check(m)?;
+
+        for crates in crane.lifts(m.n) {
+            self.lift(crates, m.from, m.to);
+        }
+
+        Ok(())
     }
 
-    fn rearrange(&mut self, from: usize, to: usize) {
-        let c = self.0[from - 1].pop().unwrap();
-        self.0[to - 1].push(c);
+    /// Reverts `m` as applied by the given crane.
+    fn unapply(&mut self, crane: &dyn Crane, m: &Move) {
+        for crates in crane.lifts(m.n).into_iter().rev() {
+            self.lift(crates, m.to, m.from);
+        }
     }
 
-    fn rearrange_9001(&mut self, crates: usize, from: usize, to: usize) {
+    fn lift(&mut self, crates: usize, from: usize, to: usize) {
         let c = self.0[from - 1].pop_many(crates);
         self.0[to - 1].push_many(c);
     }
@@ -69,36 +287,397 @@ impl Stacks {
     fn tops(&self) -> String {
         let mut s = String::new();
         for v in &self.0 {
-            s.push(v.top().unwrap_or(&' ').clone());
+            s.push(*v.top().unwrap_or(&' '));
         }
 
         s
     }
 }
 
+/// Stacks together with the moves applied to them, which can be undone,
+/// redone and replayed.
+#[derive(Debug)]
+struct MoveLog {
+    stacks: Stacks,
+    crane: Box<dyn Crane>,
+    moves: Vec<Move>,
+    applied: usize,
+}
+
+impl MoveLog {
+    fn new(stacks: Stacks, crane: Box<dyn Crane>) -> Self {
+        MoveLog {
+            stacks,
+            crane,
+            moves: vec![],
+            applied: 0,
+        }
+    }
+
+    fn stacks(&self) -> &Stacks {
+        &self.stacks
+    }
+
+    /// Returns the number of lifts made by the applied moves.
+    fn lifts(&self) -> usize {
+        self.moves[..self.applied]
+            .iter()
+            .map(|m| self.crane.lifts(m.n).len())
+            .sum()
+    }
+
+    /// Applies and records `m`, forgetting any undone moves.
+    fn push(&mut self, m: Move) -> Result<(), ParseError> {
+        self.stacks.apply(self.crane.as_ref(), &m)?;
+        self.moves.truncate(self.applied);
+        self.moves.push(m);
+        self.applied += 1;
+
+        Ok(())
+    }
+
+    /// Reverts the last applied move. Returns false if there is none.
+    fn undo(&mut self) -> bool {
+        if self.applied == 0 {
+            return false;
+        }
+
+        self.applied -= 1;
+        self.stacks
+            .unapply(self.crane.as_ref(), &self.moves[self.applied]);
+
+        true
+    }
+
+    /// Applies the next undone move again. Returns false if there is none.
+    fn redo(&mut self) -> bool {
+        let Some(m) = self.moves.get(self.applied) else {
+            return false;
+        };
+
+        self.stacks
+            .apply(self.crane.as_ref(), m)
+            .expect("redoing an undone move");
+        self.applied += 1;
+
+        true
+    }
+
+    /// Undoes or redoes moves until exactly `step` are applied. Returns
+    /// false if fewer than `step` moves have been recorded.
+    fn replay_to(&mut self, step: usize) -> bool {
+        if step > self.moves.len() {
+            return false;
+        }
+
+        while self.applied > step {
+            self.undo();
+        }
+        while self.applied < step {
+            self.redo();
+        }
+
+        true
+    }
+}
+
+/// Redraws the stacks of `log` after each of its moves, from the first to
+/// the last, waiting `delay` between frames.
+fn animate(log: &mut MoveLog, delay: Duration) {
+    log.replay_to(0);
+
+    loop {
+        // Clear the terminal and move the cursor to the top left corner.
+        println!("\x1b[2J\x1b[H{}", log.stacks());
+        match log.applied.checked_sub(1) {
+            Some(i) => println!("{} ({}/{})", log.moves[i], log.applied, log.moves.len()),
+            None => println!("start (0/{})", log.moves.len()),
+        }
+
+        if !log.redo() {
+            break;
+        }
+        thread::sleep(delay);
+    }
+}
+
 fn main() {
-    let contents = std::fs::read_to_string("inputs/5.txt").expect("read input");
+    let mut step = None;
+    let mut cranes: Vec<Box<dyn Crane>> = vec![];
+    let mut animated = None;
+    let mut delay = Duration::from_millis(100);
+    let mut write = false;
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == "--step" {
+            step = args.next().and_then(|k| k.parse::<usize>().ok());
+            assert!(step.is_some(), "--step needs a number");
+        } else if arg == "--crane" {
+            let crane = args.next().and_then(|c| parse_crane(&c));
+            cranes.push(crane.expect("--crane needs 9000, 9001 or limited:k"));
+        } else if arg == "--animate" {
+            let crane = args.next().and_then(|c| parse_crane(&c));
+            animated = Some(crane.expect("--animate needs 9000, 9001 or limited:k"));
+        } else if arg == "--delay" {
+            delay = args
+                .next()
+                .and_then(|ms| ms.parse().ok())
+                .map(Duration::from_millis)
+                .expect("--delay needs a number of milliseconds");
+        } else if arg == "--write" {
+            write = true;
+        }
+    }
+
+    aoc2022::run(5, |contents| {
+        let (stacks, moves, offset) = parse_input(contents)?;
+
+        // Without --crane, print the answer of the CrateMover 9001.
+        let compare = !cranes.is_empty();
+        let labelled: Vec<(String, Box<dyn Crane>)> = if compare {
+            cranes
+                .into_iter()
+                .map(|crane| (format!("tops {}", crane), crane))
+                .collect()
+        } else {
+            vec![("tops".to_string(), Box::new(CrateMover9001))]
+        };
+
+        let (labels, mut logs): (Vec<String>, Vec<MoveLog>) = labelled
+            .into_iter()
+            .map(|(label, crane)| (label, MoveLog::new(stacks.clone(), crane)))
+            .unzip();
+        let mut animated = animated.map(|crane| MoveLog::new(stacks, crane));
+
+        for (i, m) in moves.into_iter().enumerate() {
+            for log in logs.iter_mut().chain(animated.as_mut()) {
+                log.push(m).map_err(|e| e.offset_lines(offset + i))?;
+            }
+        }
 
-    let mut iter = contents.split("\n\n");
+        if let Some(log) = &mut animated {
+            animate(log, delay);
+        }
+
+        if let Some(step) = step {
+            for log in &mut logs {
+                assert!(log.replay_to(step), "there is no step {}", step);
+            }
+        }
+
+        if write {
+            // Write what is left of the puzzle after the requested step, as
+            // seen by the first crane.
+            let log = &mut logs[0];
+            log.replay_to(step.unwrap_or(0));
+            print!("{}", write_input(log.stacks(), &log.moves[log.applied..]));
+
+            return Ok(());
+        }
+
+        for (label, log) in labels.iter().zip(&logs) {
+            if compare {
+                println!("{}: {} ({} lifts)", label, log.stacks().tops(), log.lifts());
+            } else {
+                println!("{}: {}", label, log.stacks().tops());
+            }
+        }
+
+        Ok(())
+    });
+}
 
-    let diagram = iter.next().unwrap();
-    let mut stacks = Stacks::from(diagram);
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let instructions = iter.next().unwrap();
+    const EXAMPLE: &str = "    [D]    
+[N] [C]    
+[Z] [M] [P]
+ 1   2   3 
 
-    let re = regex::Regex::new(r#"^move (\d+) from (\d+) to (\d+)$"#).unwrap();
+move 1 from 2 to 1
+move 3 from 1 to 3
+move 2 from 2 to 1
+move 1 from 1 to 2
+";
 
-    for instruction in instructions.split_terminator("\n") {
-        if let Some(captures) = re.captures(instruction) {
-            let n = captures.get(1).unwrap().as_str().parse::<usize>().unwrap();
-            let from = captures.get(2).unwrap().as_str().parse::<usize>().unwrap();
-            let to = captures.get(3).unwrap().as_str().parse::<usize>().unwrap();
+    fn example(crane: impl Crane + 'static) -> MoveLog {
+        let (stacks, moves, _) = parse_input(EXAMPLE).unwrap();
 
-            // for _i in 0..n {
-            stacks.rearrange_9001(n, from, to);
-            // }
+        let mut log = MoveLog::new(stacks, Box::new(crane));
+        for m in moves {
+            log.push(m).unwrap();
         }
+
+        log
+    }
+
+    #[test]
+    fn undo_redo() {
+        let mut log = example(CrateMover9000);
+        let mut log_9001 = example(CrateMover9001);
+
+        assert_eq!(log.stacks().tops(), "CMZ");
+        assert_eq!(log_9001.stacks().tops(), "MCD");
+
+        assert!(log.replay_to(1));
+        assert_eq!(log.stacks().tops(), "DCP");
+
+        while log_9001.undo() {}
+        assert_eq!(log_9001.applied, 0);
+        assert_eq!(log_9001.stacks().tops(), "NDP");
+
+        assert!(log_9001.redo());
+        assert!(log_9001.replay_to(4));
+        assert!(!log_9001.redo());
+        assert!(!log_9001.replay_to(5));
+        assert_eq!(log_9001.stacks().tops(), "MCD");
+    }
+
+    #[test]
+    fn push_forgets_undone_moves() {
+        let mut log = example(CrateMover9000);
+
+        log.replay_to(2);
+        log.push(Move {
+            n: 1,
+            from: 3,
+            to: 2,
+        })
+        .unwrap();
+
+        assert_eq!(log.applied, 3);
+        assert!(!log.redo());
+    }
+
+    #[test]
+    fn cranes() {
+        let limited = LimitedCrane(2);
+
+        assert_eq!(limited.lifts(5), [2, 2, 1]);
+        assert_eq!(limited.lifts(4), [2, 2]);
+        assert_eq!(parse_crane("limited:2").unwrap().to_string(), "limited:2");
+        assert!(parse_crane("limited:0").is_none());
+        assert!(parse_crane("9002").is_none());
+
+        // The second move lifts N and D off stack 1 together, then Z.
+        let mut log = example(LimitedCrane(2));
+
+        assert_eq!(log.stacks().tops(), "MCZ");
+        assert_eq!(log.lifts(), 5);
+
+        log.replay_to(2);
+        assert_eq!(log.stacks().0[2].0, ['P', 'N', 'D', 'Z']);
+
+        let mut start = example(CrateMover9001);
+        start.replay_to(0);
+        log.replay_to(0);
+
+        assert_eq!(log.stacks(), start.stacks());
+    }
+
+    #[test]
+    fn render() {
+        let mut log = example(CrateMover9001);
+
+        assert_eq!(
+            log.stacks().to_string(),
+            "        [D]
+        [N]
+        [Z]
+[M] [C] [P]
+ 1   2   3 
+"
+        );
+
+        log.replay_to(0);
+
+        assert!(EXAMPLE.starts_with(&log.stacks().to_string()));
     }
 
-    println!("tops: {}", stacks.tops());
+    #[test]
+    fn round_trip() {
+        for input in [EXAMPLE, include_str!("../inputs/5.txt")] {
+            let (stacks, moves, _) = parse_input(input).unwrap();
+
+            assert_eq!(write_input(&stacks, &moves), input);
+        }
+    }
+
+    #[test]
+    fn round_trip_without_moves() {
+        let mut log = example(CrateMover9001);
+        let input = write_input(log.stacks(), &[]);
+
+        assert!(input.ends_with(" 1   2   3 \n\n"));
+
+        let (stacks, moves, _) = parse_input(&input).unwrap();
+
+        assert_eq!(&stacks, log.stacks());
+        assert!(moves.is_empty());
+        assert_eq!(write_input(&stacks, &moves), input);
+
+        log.replay_to(0);
+        let (stacks, moves, _) = parse_input(&write_input(log.stacks(), &[])).unwrap();
+
+        assert_eq!(&stacks, log.stacks());
+        assert!(moves.is_empty());
+    }
+
+    #[test]
+    fn many_stacks() {
+        let stacks = Stacks(
+            ('A'..='L')
+                .map(|c| Stack(vec![c; (c as usize) % 3 + 1]))
+                .collect(),
+        );
+        let moves = [Move {
+            n: 2,
+            from: 12,
+            to: 10,
+        }];
+
+        let input = write_input(&stacks, &moves);
+
+        assert!(input.contains(" 9  10  11  12 \n"));
+
+        let (parsed, parsed_moves, _) = parse_input(&input).unwrap();
+
+        assert_eq!(parsed, stacks);
+        assert_eq!(write_input(&parsed, &parsed_moves), input);
+
+        let e = Stacks::try_from("[A] [B]\n 1   3 ").unwrap_err();
+
+        assert_eq!((e.line_no, e.column), (2, 6));
+        assert_eq!(e.reason, "expected stack number 2, found \"3\"");
+    }
+
+    #[test]
+    fn invalid_moves() {
+        let mut log = example(CrateMover9000);
+        let before = log.stacks().clone();
+
+        let e = log
+            .push(Move {
+                n: 1,
+                from: 4,
+                to: 1,
+            })
+            .unwrap_err();
+        assert_eq!(e.reason, "no stack 4, there are 3");
+
+        let e = log
+            .push(Move {
+                n: 2,
+                from: 2,
+                to: 1,
+            })
+            .unwrap_err();
+        assert_eq!(e.reason, "cannot move 2 crates from stack 2 holding 1");
+
+        assert_eq!(log.stacks(), &before);
+        assert_eq!(log.applied, 4);
+    }
 }
@@ -1,15 +1,79 @@
-use std::collections::HashSet;
-fn main() {
-    let contents = std::fs::read_to_string("inputs/6.txt").expect("read input");
+use std::io::{self, BufRead};
+
+use aoc2022::parse::ParseError;
+
+/// Returns the number of characters read from `input` when the last
+/// `unique_len` of them were all different, reading one buffer at a time
+/// and only remembering the current window.
+fn find_marker<R: BufRead>(mut input: R, unique_len: usize) -> io::Result<Option<usize>> {
+    let mut window = std::collections::VecDeque::with_capacity(unique_len);
+    let mut counts = [0usize; 256];
+    let mut distinct = 0;
+    let mut read = 0;
+
+    loop {
+        let buf = input.fill_buf()?;
+        if buf.is_empty() {
+            return Ok(None);
+        }
+
+        for (i, &b) in buf.iter().enumerate() {
+            if window.len() == unique_len {
+                let old: u8 = window.pop_front().unwrap();
+                counts[old as usize] -= 1;
+                if counts[old as usize] == 0 {
+                    distinct -= 1;
+                }
+            }
+
+            window.push_back(b);
+            counts[b as usize] += 1;
+            if counts[b as usize] == 1 {
+                distinct += 1;
+            }
+
+            if distinct == unique_len {
+                return Ok(Some(read + i + 1));
+            }
+        }
+
+        let len = buf.len();
+        read += len;
+        input.consume(len);
+    }
+}
 
+fn main() {
     let unique_len = 14;
 
-    if let Some(pos) = contents
-        .as_bytes()
-        .windows(unique_len)
-        .position(|w| w.iter().copied().collect::<HashSet<u8>>().len() == unique_len)
-    {
-        println!("position: {}", pos);
-        println!("characters: {}", pos + unique_len);
+    let input = aoc2022::open_input(6, std::env::args().nth(1))
+        .unwrap_or_else(|e| aoc2022::exit_with_error(6, e));
+    let marker = find_marker(input, unique_len).unwrap_or_else(|e| {
+        aoc2022::exit_with_error(
+            6,
+            ParseError::unlocated(format!("cannot read input: {}", e)),
+        )
+    });
+
+    if let Some(characters) = marker {
+        println!("position: {}", characters - unique_len);
+        println!("characters: {}", characters);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn markers() {
+        let signal = "mjqjpqmgbljsphdztnvjfqwrcgsmlb";
+
+        assert_eq!(find_marker(signal.as_bytes(), 4).unwrap(), Some(7));
+        assert_eq!(find_marker(signal.as_bytes(), 14).unwrap(), Some(19));
+        assert_eq!(find_marker("aabbcc".as_bytes(), 4).unwrap(), None);
+
+        let tiny_buffers = io::BufReader::with_capacity(3, signal.as_bytes());
+        assert_eq!(find_marker(tiny_buffers, 14).unwrap(), Some(19));
     }
 }
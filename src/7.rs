@@ -1,5 +1,7 @@
 use std::{collections::HashMap, path::PathBuf};
 
+use aoc2022::parse::{parse_lines, ParseError};
+
 #[derive(Debug)]
 enum Line {
     Command(Command),
@@ -19,37 +21,44 @@ enum Output {
 }
 
 #[derive(Debug)]
+#[allow(dead_code)]
 enum FsEntry {
     File(usize, String),
     Dir(usize, String),
 }
 
-fn parse_line(line: &str) -> Line {
+fn parse_line(line: &str) -> Result<Line, ParseError> {
     if let Some(command) = line.strip_prefix("$ ") {
         if let Some(dir) = command.strip_prefix("cd ") {
-            Line::Command(Command::Cd(dir.to_owned()))
+            Ok(Line::Command(Command::Cd(dir.to_owned())))
         } else if command == "ls" {
-            Line::Command(Command::Ls)
+            Ok(Line::Command(Command::Ls))
         } else {
-            panic!("wtf command")
+            Err(ParseError::new(3, format!("unknown command {:?}", command)))
         }
     } else if let Some(dir) = line.strip_prefix("dir ") {
-        Line::Output(Output::Dir(dir.to_owned()))
+        Ok(Line::Output(Output::Dir(dir.to_owned())))
     } else {
-        let (s, n) = line.split_once(' ').expect("find space");
-        Line::Output(Output::File(s.parse().unwrap(), n.to_owned()))
+        let (s, n) = line
+            .split_once(' ')
+            .ok_or_else(|| ParseError::new(1, "expected file size and name"))?;
+        let size = s
+            .parse()
+            .map_err(|e| ParseError::new(1, format!("cannot parse size {:?}: {}", s, e)))?;
+
+        Ok(Line::Output(Output::File(size, n.to_owned())))
     }
 }
 
-fn main() {
-    let contents = std::fs::read_to_string("inputs/7.txt").expect("read input");
-
+/// Replays the terminal session, returning every file and directory by
+/// path with directory sizes including everything below them.
+fn build_file_system(contents: &str) -> Result<HashMap<PathBuf, FsEntry>, ParseError> {
     let mut file_system = HashMap::<PathBuf, FsEntry>::new();
     let mut cwd = PathBuf::from("/");
 
     file_system.insert(cwd.clone(), FsEntry::Dir(0, String::from("/")));
 
-    for l in contents.lines().map(parse_line) {
+    for (i, l) in parse_lines(contents, parse_line)?.into_iter().enumerate() {
         match l {
             Line::Command(c) => match c {
                 Command::Cd(dir) => {
@@ -58,7 +67,22 @@ fn main() {
                     } else if &dir == ".." {
                         cwd.pop();
                     } else {
-                        cwd.push(dir);
+                        cwd.push(&dir);
+                    }
+
+                    match file_system.get(&cwd) {
+                        Some(FsEntry::Dir(..)) => (),
+                        Some(FsEntry::File(..)) => {
+                            return Err(ParseError::new(
+                                6,
+                                format!("{:?} is a file, not a directory", dir),
+                            )
+                            .offset_lines(i))
+                        }
+                        None => {
+                            return Err(ParseError::new(6, format!("unknown directory {:?}", dir))
+                                .offset_lines(i))
+                        }
                     }
                 }
                 Command::Ls => (),
@@ -67,66 +91,129 @@ fn main() {
                 Output::Dir(dir) => {
                     let mut new_dir = cwd.clone();
                     new_dir.push(&dir);
-                    file_system.insert(new_dir, FsEntry::Dir(0, dir));
+
+                    if let Some(FsEntry::File(..)) = file_system.get(&new_dir) {
+                        return Err(ParseError::new(
+                            5,
+                            format!("{:?} is already listed as a file", dir),
+                        )
+                        .offset_lines(i));
+                    }
+                    file_system.entry(new_dir).or_insert(FsEntry::Dir(0, dir));
                 }
                 Output::File(sz, name) => {
-                    let e = file_system.get_mut(&cwd).unwrap();
-                    match e {
-                        FsEntry::Dir(ref mut dir_size, _) => {
-                            *dir_size += sz;
-                        }
-                        _ => panic!("wtf"),
-                    };
+                    let mut new_file = cwd.clone();
+                    new_file.push(&name);
 
-                    let mut itercwd = cwd.clone();
+                    if let Some(FsEntry::Dir(..)) = file_system.get(&new_file) {
+                        return Err(ParseError::new(
+                            1,
+                            format!("{:?} is already listed as a directory", name),
+                        )
+                        .offset_lines(i));
+                    }
 
-                    while itercwd.pop() {
-                        let e = file_system.get_mut(&itercwd).unwrap();
-                        match e {
-                            FsEntry::Dir(ref mut dir_size, _) => {
-                                *dir_size += sz;
-                            }
-                            _ => panic!("wtf"),
-                        };
+                    // Only directories are ever entered, so the current
+                    // directory and all its parents are directories.
+                    let mut itercwd = cwd.clone();
+                    loop {
+                        if let Some(FsEntry::Dir(dir_size, _)) = file_system.get_mut(&itercwd) {
+                            *dir_size += sz;
+                        }
+                        if !itercwd.pop() {
+                            break;
+                        }
                     }
 
-                    let mut new_file = cwd.clone();
-                    new_file.push(&name);
                     file_system.insert(new_file, FsEntry::File(sz, name));
                 }
             },
         }
     }
 
-    let sizes: usize = file_system
-        .values()
-        .filter_map(|entry| match entry {
-            &FsEntry::Dir(sz, _) if sz <= 100000 => Some(sz),
-            _ => None,
-        })
-        .sum();
+    Ok(file_system)
+}
 
-    println!("size: {}", sizes);
+fn main() {
+    aoc2022::run(7, |contents| {
+        let file_system = build_file_system(contents)?;
+
+        let sizes: usize = file_system
+            .values()
+            .filter_map(|entry| match entry {
+                &FsEntry::Dir(sz, _) if sz <= 100000 => Some(sz),
+                _ => None,
+            })
+            .sum();
+
+        println!("size: {}", sizes);
+
+        let needed_free: usize = 30000000;
+
+        let total: usize = 70000000;
+
+        let used: usize = match file_system.get(&PathBuf::from("/")) {
+            Some(FsEntry::Dir(sz, _)) => *sz,
+            _ => unreachable!("the root is always a directory"),
+        };
+
+        let unused = total.checked_sub(used).ok_or_else(|| {
+            ParseError::unlocated(format!("{} bytes used on a {} byte disk", used, total))
+        })?;
+        let to_free = needed_free.saturating_sub(unused);
+
+        let smallest = file_system
+            .values()
+            .filter_map(|entry| match entry {
+                &FsEntry::Dir(sz, _) if sz >= to_free => Some(sz),
+                _ => None,
+            })
+            .min()
+            .ok_or_else(|| ParseError::unlocated("no directory is big enough to delete"))?;
+
+        println!("size of smallest bigger: {}", smallest);
+
+        Ok(())
+    });
+}
 
-    let needed_free: usize = 30000000;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    let total: usize = 70000000;
+    fn dir_size(file_system: &HashMap<PathBuf, FsEntry>, path: &str) -> usize {
+        match file_system[&PathBuf::from(path)] {
+            FsEntry::Dir(sz, _) => sz,
+            FsEntry::File(..) => panic!("{} is a file", path),
+        }
+    }
 
-    let used: usize = match file_system.get(&PathBuf::from("/")).unwrap() {
-        FsEntry::Dir(sz, _) => *sz,
-        _ => panic!(),
-    };
+    #[test]
+    fn sizes() {
+        let fs =
+            build_file_system("$ cd /\n$ ls\ndir a\n10 b\n$ cd a\n$ ls\n5 c\n$ cd ..\n$ ls\ndir a")
+                .unwrap();
 
-    let unused = total - used;
+        assert_eq!(dir_size(&fs, "/"), 15);
+        assert_eq!(dir_size(&fs, "/a"), 5);
+    }
 
-    let mut dirs = file_system
-        .values()
-        .filter_map(|entry| match entry {
-            &FsEntry::Dir(sz, ref path) if sz >= (needed_free - unused) => Some((sz, path.clone())),
-            _ => None,
-        })
-        .collect::<Vec<(usize, String)>>();
+    #[test]
+    fn errors() {
+        let e = build_file_system("$ ls\n100 a\n$ cd a\n$ ls\n5 b").unwrap_err();
+        assert_eq!((e.line_no, e.column), (3, 6));
+        assert_eq!(e.reason, "\"a\" is a file, not a directory");
 
-    dirs.sort_by(|lhs, rhs| lhs.0.cmp(&rhs.0));
-    println!("size of smallest bigger: {}", dirs[0].0);
+        let e = build_file_system("$ cd /\n$ cd foo").unwrap_err();
+        assert_eq!(e.line_no, 2);
+        assert_eq!(e.reason, "unknown directory \"foo\"");
+
+        let e = build_file_system("$ ls\ndir a\n7 a").unwrap_err();
+        assert_eq!(e.line_no, 3);
+        assert_eq!(e.reason, "\"a\" is already listed as a directory");
+
+        let e = build_file_system("$ ls\n7 a\ndir a").unwrap_err();
+        assert_eq!((e.line_no, e.column), (3, 5));
+        assert_eq!(e.reason, "\"a\" is already listed as a file");
+    }
 }
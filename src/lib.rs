@@ -0,0 +1,57 @@
+extern crate self as aoc2022;
+
+pub mod parse;
+
+use std::io::BufRead;
+
+use parse::ParseError;
+
+/// Opens the input for `day` for reading piece by piece, for solvers that
+/// should not hold the whole input in memory. The input is
+/// `inputs/<day>.txt` unless `path` is given, where `-` means standard
+/// input.
+pub fn open_input(day: u8, path: Option<String>) -> Result<Box<dyn BufRead>, ParseError> {
+    let path = path.unwrap_or_else(|| format!("inputs/{}.txt", day));
+
+    if path == "-" {
+        Ok(Box::new(std::io::stdin().lock()))
+    } else {
+        let file = std::fs::File::open(&path)
+            .map_err(|e| ParseError::unlocated(format!("cannot open {}: {}", path, e)))?;
+        Ok(Box::new(std::io::BufReader::new(file)))
+    }
+}
+
+/// Reads the input for `day` from the `inputs` directory and passes it to
+/// `solve`. If `solve` fails, the error is printed together with the
+/// offending line of the input and the process exits.
+pub fn run<F>(day: u8, solve: F)
+where
+    F: FnOnce(&str) -> Result<(), ParseError>,
+{
+    let path = format!("inputs/{}.txt", day);
+    let contents = std::fs::read_to_string(&path).unwrap_or_else(|e| {
+        exit_with_error(
+            day,
+            ParseError::unlocated(format!("cannot read {}: {}", path, e)),
+        )
+    });
+
+    if let Err(mut e) = solve(&contents) {
+        if e.is_located() && e.line.is_empty() {
+            if let Some(line) = contents.lines().nth(e.line_no.saturating_sub(1)) {
+                e.line = line.to_owned();
+            }
+        }
+
+        exit_with_error(day, e);
+    }
+}
+
+/// Prints `e` for `day` together with the offending line and exits.
+pub fn exit_with_error(day: u8, mut e: ParseError) -> ! {
+    e.day = Some(day);
+
+    eprint!("{}", e.report());
+    std::process::exit(1);
+}
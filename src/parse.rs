@@ -0,0 +1,461 @@
+//! Helpers for turning puzzle input into values without panicking.
+//!
+//! Line and column numbers in [ParseError] start at 1 and are relative to
+//! the text passed to the helper. Use [ParseError::offset_lines] to move an
+//! error from a block or a single line to its place in the whole input.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+pub use from_line_derive::FromLine;
+
+/// An error in the puzzle input, pointing at where it was found. Errors
+/// about the input as a whole, made with [ParseError::unlocated], have a
+/// `line_no` of 0 and point nowhere.
+///
+/// The day and the text of the offending line are filled in by
+/// [crate::run], which knows the whole input.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseError {
+    pub day: Option<u8>,
+    pub line_no: usize,
+    pub column: usize,
+    pub line: String,
+    pub reason: String,
+}
+
+impl ParseError {
+    /// Creates an error at the given column of the first line.
+    pub fn new(column: usize, reason: impl Into<String>) -> Self {
+        ParseError {
+            day: None,
+            line_no: 1,
+            column,
+            line: String::new(),
+            reason: reason.into(),
+        }
+    }
+
+    /// Creates an error about the input as a whole rather than a place in
+    /// it.
+    pub fn unlocated(reason: impl Into<String>) -> Self {
+        ParseError {
+            line_no: 0,
+            column: 0,
+            ..ParseError::new(0, reason)
+        }
+    }
+
+    /// Returns true if the error points at a place in the input.
+    pub fn is_located(&self) -> bool {
+        self.line_no > 0
+    }
+
+    /// Moves the error `n` lines further down.
+    pub fn offset_lines(mut self, n: usize) -> Self {
+        if self.is_located() {
+            self.line_no += n;
+        }
+        self
+    }
+
+    /// Moves the error `n` columns to the right.
+    pub fn offset_columns(mut self, n: usize) -> Self {
+        if self.is_located() {
+            self.column += n;
+        }
+        self
+    }
+
+    /// Returns the error followed by the offending line with a caret under
+    /// the column it points at, if there is one.
+    pub fn report(&self) -> String {
+        if !self.is_located() {
+            return format!("error: {}\n", self);
+        }
+
+        let number = self.line_no.to_string();
+        let gutter = " ".repeat(number.len());
+
+        format!(
+            "error: {}\n{} |\n{} | {}\n{} | {}^\n",
+            self,
+            gutter,
+            number,
+            self.line,
+            gutter,
+            " ".repeat(self.column.saturating_sub(1))
+        )
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(day) = self.day {
+            write!(f, "day {}, ", day)?;
+        }
+
+        if !self.is_located() {
+            return write!(f, "{}", self.reason);
+        }
+
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line_no, self.column, self.reason
+        )
+    }
+}
+
+impl From<grid::ParseMatrixError> for ParseError {
+    fn from(e: grid::ParseMatrixError) -> Self {
+        let (row, column) = match e {
+            grid::ParseMatrixError::Ragged { row, len, expected } => (row, len.min(expected)),
+            grid::ParseMatrixError::NotADigit { row, column, .. } => (row, column),
+        };
+
+        ParseError::new(column + 1, e.to_string()).offset_lines(row)
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+/// Parses every line of `input` with `f`, fixing up the line number of any
+/// error so it points into `input`.
+pub fn parse_lines<'a, T, F>(input: &'a str, mut f: F) -> Result<Vec<T>, ParseError>
+where
+    F: FnMut(&'a str) -> Result<T, ParseError>,
+{
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| f(line).map_err(|e| e.offset_lines(i)))
+        .collect()
+}
+
+/// Splits `input` into blocks of lines separated by one or more blank lines.
+/// Each block comes with the number of lines before it, to be passed to
+/// [ParseError::offset_lines].
+pub fn blocks(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    let mut blocks = vec![];
+    let mut start: Option<(usize, usize)> = None;
+    let mut offset = 0;
+
+    for (i, line) in input.split_inclusive('\n').enumerate() {
+        let blank = line.trim().is_empty();
+
+        match (start, blank) {
+            (None, false) => start = Some((i, offset)),
+            (Some((first, begin)), true) => {
+                blocks.push((first, input[begin..offset].trim_end_matches(['\n', '\r'])));
+                start = None;
+            }
+            _ => (),
+        }
+
+        offset += line.len();
+    }
+
+    if let Some((first, begin)) = start {
+        blocks.push((first, input[begin..].trim_end_matches(['\n', '\r'])));
+    }
+
+    blocks.into_iter()
+}
+
+fn parse_field<T>(column: usize, s: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    s.parse()
+        .map_err(|e| ParseError::new(column, format!("cannot parse {:?}: {}", s, e)))
+}
+
+/// Returns every integer in `line`, in order. Anything that is not part of
+/// a number separates numbers. A `-` directly before a number makes it
+/// negative unless it follows a letter or digit, so `2-4` is `2` and `4`
+/// while `x=-3` is `-3`.
+pub fn extract_ints<T>(line: &str) -> Result<Vec<T>, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    let bytes = line.as_bytes();
+    let mut ints = vec![];
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let mut start = i;
+        if start > 0
+            && bytes[start - 1] == b'-'
+            && (start < 2 || !bytes[start - 2].is_ascii_alphanumeric())
+        {
+            start -= 1;
+        }
+
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        ints.push(parse_field(start + 1, &line[start..i])?);
+    }
+
+    Ok(ints)
+}
+
+/// A tuple of values that can be parsed from the fields matched by
+/// [match_line].
+pub trait FromFields: Sized {
+    /// The number of fields needed.
+    const COUNT: usize;
+
+    /// Parses the fields, each given with its column.
+    fn from_fields(fields: &[(usize, &str)]) -> Result<Self, ParseError>;
+}
+
+macro_rules! tuple_from_fields {
+    ($count:literal; $($t:ident $i:tt),+) => {
+        impl<$($t),+> FromFields for ($($t,)+)
+        where
+            $($t: FromStr, $t::Err: Display,)+
+        {
+            const COUNT: usize = $count;
+
+            fn from_fields(fields: &[(usize, &str)]) -> Result<Self, ParseError> {
+                Ok(($(parse_field::<$t>(fields[$i].0, fields[$i].1)?,)+))
+            }
+        }
+    };
+}
+
+tuple_from_fields!(1; A 0);
+tuple_from_fields!(2; A 0, B 1);
+tuple_from_fields!(3; A 0, B 1, C 2);
+tuple_from_fields!(4; A 0, B 1, C 2, D 3);
+tuple_from_fields!(5; A 0, B 1, C 2, D 3, E 4);
+tuple_from_fields!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+/// Matches `line` against `pattern`, where each `{}` in the pattern stands
+/// for a field, and parses the fields into a tuple.
+///
+/// A field extends to the first occurrence of the literal text following it
+/// in the pattern, or to the end of the line for a trailing `{}`.
+///
+/// ```
+/// # use aoc2022::parse::match_line;
+/// let (n, from, to): (usize, usize, usize) =
+///     match_line("move 3 from 1 to 2", "move {} from {} to {}").unwrap();
+/// assert_eq!((n, from, to), (3, 1, 2));
+/// ```
+pub fn match_line<T: FromFields>(line: &str, pattern: &str) -> Result<T, ParseError> {
+    let literals: Vec<&str> = pattern.split("{}").collect();
+    let fields = match_fields(line, &literals)?;
+
+    if fields.len() != T::COUNT {
+        return Err(ParseError::new(
+            1,
+            format!("pattern has {} fields, expected {}", fields.len(), T::COUNT),
+        ));
+    }
+
+    T::from_fields(&fields)
+}
+
+/// Matches `line` against a pattern given as the literal text around its
+/// fields, so `move {} from {}` is `["move ", " from ", ""]`. Returns the
+/// text of each field together with its column.
+///
+/// This is the matcher behind [match_line] and `#[derive(FromLine)]`.
+pub fn match_fields<'a>(
+    line: &'a str,
+    literals: &[&str],
+) -> Result<Vec<(usize, &'a str)>, ParseError> {
+    let (first, literals) = literals.split_first().unwrap_or((&"", &[]));
+
+    let mut rest = line
+        .strip_prefix(first)
+        .ok_or_else(|| ParseError::new(1, format!("expected {:?}", first)))?;
+    let mut fields = vec![];
+
+    for literal in literals {
+        let column = line.len() - rest.len() + 1;
+        let end = if literal.is_empty() {
+            rest.len()
+        } else {
+            rest.find(literal).ok_or_else(|| {
+                ParseError::new(column, format!("expected {:?} after field", literal))
+            })?
+        };
+
+        fields.push((column, &rest[..end]));
+        rest = &rest[end + literal.len()..];
+    }
+
+    if !rest.is_empty() {
+        let column = line.len() - rest.len() + 1;
+        return Err(ParseError::new(column, format!("unexpected {:?}", rest)));
+    }
+
+    Ok(fields)
+}
+
+/// A value that can be parsed from a single line of input, usually through
+/// `#[derive(FromLine)]` and a `#[pattern("...")]` attribute.
+///
+/// ```
+/// use aoc2022::parse::FromLine;
+///
+/// #[derive(FromLine)]
+/// #[pattern("move {n} from {from} to {to}")]
+/// struct Move {
+///     n: usize,
+///     from: usize,
+///     to: usize,
+/// }
+///
+/// let m = Move::from_line("move 3 from 1 to 2").unwrap();
+/// assert_eq!((m.n, m.from, m.to), (3, 1, 2));
+/// ```
+pub trait FromLine: Sized {
+    fn from_line(line: &str) -> Result<Self, ParseError>;
+}
+
+/// Parses the field called `name` found at `column`. Used by the code
+/// generated for `#[derive(FromLine)]`.
+pub fn parse_named_field<T>(name: &str, column: usize, s: &str) -> Result<T, ParseError>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    s.parse().map_err(|e| {
+        ParseError::new(
+            column,
+            format!("cannot parse `{}` from {:?}: {}", name, s, e),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_blocks() {
+        let input = "1000\n2000\n\n\n3000\n\n4000\n5000\n";
+
+        assert_eq!(
+            blocks(input).collect::<Vec<_>>(),
+            vec![(0, "1000\n2000"), (4, "3000"), (6, "4000\n5000")]
+        );
+        assert_eq!(blocks("").count(), 0);
+        assert_eq!(blocks("\n\na\r\n").collect::<Vec<_>>(), vec![(2, "a")]);
+    }
+
+    #[test]
+    fn ints() {
+        assert_eq!(extract_ints::<i32>("2-4,6-8"), Ok(vec![2, 4, 6, 8]));
+        assert_eq!(
+            extract_ints::<i64>("Sensor at x=-3, y=15: closest beacon is at x=-2, y=-10"),
+            Ok(vec![-3, 15, -2, -10])
+        );
+        assert_eq!(extract_ints::<u8>("no numbers"), Ok(vec![]));
+
+        let e = extract_ints::<u8>("1 300").unwrap_err();
+        assert_eq!(e.column, 3);
+
+        let e = extract_ints::<u32>("a -5").unwrap_err();
+        assert_eq!(e.column, 3);
+    }
+
+    #[test]
+    fn patterns() {
+        let m: Result<(usize, usize, usize), _> =
+            match_line("move 3 from 1 to 2", "move {} from {} to {}");
+        assert_eq!(m, Ok((3, 1, 2)));
+
+        let m: Result<(String, u32), _> = match_line("dir a b: 12", "dir {}: {}");
+        assert_eq!(m, Ok((String::from("a b"), 12)));
+
+        let m: Result<(usize, usize), _> = match_line("move 3 to 2", "move {} from {}");
+        assert_eq!(m.unwrap_err().column, 6);
+
+        let m: Result<(usize, usize), _> = match_line("move x from 2", "move {} from {}");
+        assert_eq!(m.unwrap_err().column, 6);
+
+        let m: Result<(usize,), _> = match_line("take 3!", "take {}!?");
+        assert!(m.is_err());
+    }
+
+    #[test]
+    fn report() {
+        let mut e = ParseError::new(3, "unknown shape \"Q\"").offset_lines(11);
+        e.day = Some(2);
+        e.line = String::from("A Q");
+
+        assert_eq!(
+            e.report(),
+            "error: day 2, line 12, column 3: unknown shape \"Q\"\n   |\n12 | A Q\n   |   ^\n"
+        );
+
+        let mut e = ParseError::unlocated("disk is full").offset_lines(3);
+        e.day = Some(7);
+
+        assert_eq!(e.report(), "error: day 7, disk is full\n");
+    }
+
+    #[derive(Debug, PartialEq, FromLine)]
+    #[pattern("{name} at x={x}, y={y} {{{tag}}}")]
+    struct Sensor {
+        name: String,
+        x: i32,
+        y: i32,
+        tag: char,
+    }
+
+    #[test]
+    fn derive() {
+        assert_eq!(
+            Sensor::from_line("Sensor at x=-2, y=15 {s}"),
+            Ok(Sensor {
+                name: String::from("Sensor"),
+                x: -2,
+                y: 15,
+                tag: 's'
+            })
+        );
+
+        let e = Sensor::from_line("Sensor at x=-2, y=1.5 {s}").unwrap_err();
+        assert_eq!(e.column, 19);
+        assert_eq!(
+            e.reason,
+            "cannot parse `y` from \"1.5\": invalid digit found in string"
+        );
+
+        let e = Sensor::from_line("Sensor at x=-2 y=15 {s}").unwrap_err();
+        assert_eq!(e.column, 13);
+    }
+
+    #[test]
+    fn line_numbers() {
+        let input = "1\n2\nx\n4";
+        let e = parse_lines(input, |l| match_line::<(u8,)>(l, "{}")).unwrap_err();
+
+        assert_eq!((e.line_no, e.column), (3, 1));
+
+        let (offset, block) = blocks("1\n\n2\nx").nth(1).unwrap();
+        let e = parse_lines(block, |l| match_line::<(u8,)>(l, "{}"))
+            .map_err(|e| e.offset_lines(offset))
+            .unwrap_err();
+
+        assert_eq!(
+            e.to_string(),
+            "line 4, column 1: cannot parse \"x\": invalid digit found in string"
+        );
+    }
+}